@@ -0,0 +1,544 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use radix_engine_common::prelude::*;
+use gable_simulation::*;
+use gable_simulation::checkpoint::Checkpoint;
+use gable_simulation::diagnostics::diagnose_group_deletion;
+use gable_simulation::environment::EnvironmentProfile;
+use gable_simulation::estimator::{estimate, EstimatorInput};
+use gable_simulation::holders::HolderBehaviour;
+use gable_simulation::pools::PoolRegistry;
+use gable_simulation::scenarios::{Comparison, MonteCarloSummary, Parameter, ParameterChange, PoolActivity, Scenario, ScenarioConfig, ScenarioResult, Timing};
+use gable_simulation::state_diff::StateView;
+use gable_simulation::sweep::SweepRanges;
+use gable_simulation::withdrawals::{WithdrawalFairness, WithdrawalMode};
+
+#[derive(Parser)]
+#[command(name = "gable-sim", about = "Simulations of Gable recovery plans on a fork of the Radix ledger")]
+struct Cli {
+    /// Path to the `state_manager` directory of the snapshot, `STATE_MANAGER_DATABASE_PATH` is used if not set
+    #[arg(long, global = true)]
+    database_path: Option<PathBuf>,
+    /// Environment profile with the network of the snapshot and a `[name]` section per pool with `component`, `validator` and `owner_account` addresses
+    #[arg(long, global = true)]
+    profile: Option<PathBuf>,
+    /// File with configured pools, a `[name]` section per pool, replacing the pools of the profile
+    #[arg(long, global = true)]
+    pools: Option<PathBuf>,
+    /// Name of the simulated pool from `--pools` or the profile, the first pool of the profile (or the Gable pool on mainnet) if not set
+    #[arg(long, global = true)]
+    pool: Option<String>,
+    /// State version of the snapshot, stamped on output files with the metadata read from the snapshot
+    #[arg(long, global = true)]
+    state_version: Option<u64>,
+    /// Seed of the random NFT selection
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+    /// Directory where csv files are written
+    #[arg(long, global = true, default_value = ".")]
+    output_dir: PathBuf,
+    /// Maximum number of simulated days
+    #[arg(long, global = true, default_value_t = 360)]
+    days: u64,
+    /// Days at the end of which scenario checkpoints are written to the output directory
+    #[arg(long, global = true, value_delimiter = ',')]
+    checkpoint_days: Vec<u64>,
+    /// Execute recovery plan operations with the auth module enabled, signed by simulated accounts
+    #[arg(long, global = true)]
+    auth: bool,
+    /// Collect and decode events of every transaction, written to `<scenario>_events.csv`
+    #[arg(long, global = true)]
+    events: bool,
+    /// LSU deposited every day by a new supplier
+    #[arg(long, global = true, value_parser = parse_decimal)]
+    daily_lsu_deposit: Option<Decimal>,
+    /// XRD amount of a flash loan taken and repaid every day, can be repeated
+    #[arg(long = "daily-flash-loan", global = true, value_parser = parse_decimal)]
+    daily_flash_loans: Vec<Decimal>,
+    /// Interest rate set by the admin at the start of a day, `day=rate`, can be repeated
+    #[arg(long = "interest-rate", global = true, value_parser = parse_interest_rate_change)]
+    interest_rate_changes: Vec<ParameterChange>,
+    /// Box size set by the admin at the start of a day, `day=size`, can be repeated
+    #[arg(long = "box-size", global = true, value_parser = parse_box_size_change)]
+    box_size_changes: Vec<ParameterChange>,
+    /// Process days as timed events with the daily reward arriving in given number of parts, unlocks and unstakes are finished at the epoch they mature
+    #[arg(long, global = true)]
+    reward_arrivals: Option<u64>,
+    /// Probability that an NFT holder checks the pool on a given day
+    #[arg(long, global = true, default_value_t = 1.0, value_parser = parse_probability)]
+    check_in_probability: f64,
+    /// Holders do not withdraw XRD claims smaller than this
+    #[arg(long, global = true, value_parser = parse_decimal)]
+    min_claim_xrd: Option<Decimal>,
+    /// Holders of NFTs with at least this much LSU wait until all other active holders have withdrawn
+    #[arg(long, global = true, value_parser = parse_decimal)]
+    whale_lsu: Option<Decimal>,
+    /// Share of NFT holders who never withdraw
+    #[arg(long, global = true, default_value_t = 0.0, value_parser = parse_probability)]
+    inactive_share: f64,
+    /// How NFTs are withdrawn: `whole` NFTs with `withdraw_lsu`, or `pro-rata` slices of every claim through a hypothetical entry point
+    #[arg(long, global = true, value_parser = parse_withdrawal_mode, default_value = "whole")]
+    withdrawal_mode: WithdrawalMode,
+    /// Checkpoint file to resume scenarios from instead of starting on the snapshot
+    #[arg(long, global = true)]
+    resume: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a single scenario
+    Run {
+        #[arg(value_parser = parse_scenario)]
+        scenario: Scenario,
+    },
+    /// Run all scenarios and compare days to recovery
+    Compare,
+    /// Run a scenario several times with consecutive seeds
+    Montecarlo {
+        #[arg(value_parser = parse_scenario, default_value = "current")]
+        scenario: Scenario,
+        #[arg(long, default_value_t = 10)]
+        runs: u64,
+    },
+    /// Print Gable state from the snapshot
+    #[command(subcommand)]
+    Inspect(InspectCommand),
+    /// Extract data from the snapshot
+    #[command(subcommand)]
+    Fixture(FixtureCommand),
+    /// Summarise results of previous runs in the output directory
+    Report,
+    /// Run `.rtm` transaction manifests one after another against the fork
+    Manifest {
+        paths: Vec<PathBuf>,
+        /// Additional placeholder `name=value`, used as `${name}` in the manifest
+        #[arg(long = "var", value_parser = parse_variable)]
+        variables: Vec<(String, String)>,
+        /// Initial proof (non fungible global id) added to signer proofs of simulated accounts, needs `--auth`
+        #[arg(long = "proof", value_parser = parse_proof)]
+        proofs: Vec<NonFungibleGlobalId>,
+    },
+    /// Estimate days to recovery from pool aggregates without running the fork
+    Estimate {
+        #[arg(value_parser = parse_scenario, default_value = "current")]
+        scenario: Scenario,
+        /// Also run the scenario on the fork and report the error of the estimate
+        #[arg(long)]
+        compare: bool,
+    },
+    /// Run a scenario with whole NFT and pro-rata withdrawals and compare speed and fairness
+    Withdrawals {
+        #[arg(value_parser = parse_scenario, default_value = "current")]
+        scenario: Scenario,
+    },
+    /// Run a scenario for every combination of parameter values on parallel forks
+    Sweep {
+        #[arg(value_parser = parse_scenario, default_value = "current")]
+        scenario: Scenario,
+        /// XRD added as validator reward every day, comma separated
+        #[arg(long, value_delimiter = ',', value_parser = parse_decimal, default_value = "8000")]
+        rewards: Vec<Decimal>,
+        /// Epochs added every day, comma separated
+        #[arg(long, value_delimiter = ',', default_value = "288")]
+        epochs_per_day: Vec<u64>,
+        /// NFTs with most LSU withdrawn last, comma separated, scenario default if not set
+        #[arg(long, value_delimiter = ',')]
+        held_back: Vec<usize>,
+        /// LSU deposited every day by a new supplier, comma separated, `--daily-lsu-deposit` if not set
+        #[arg(long, value_delimiter = ',', value_parser = parse_decimal)]
+        lsu_deposits: Vec<Decimal>,
+        /// Number of scenarios run at the same time
+        #[arg(long, default_value_t = 4)]
+        jobs: usize,
+    },
+    /// Show changes of Gable and validator state between two checkpoints
+    Diff {
+        /// Checkpoint file or `snapshot` for the state of the database snapshot
+        from: String,
+        /// Checkpoint file or `snapshot` for the state of the database snapshot
+        to: String,
+    },
+    /// Replay past transactions of the network on the snapshot and compare the result with a later snapshot
+    Replay {
+        /// File with one hex encoded notarized transaction per line
+        transactions: PathBuf,
+        /// Path to the `state_manager` directory of a snapshot taken after the transactions
+        #[arg(long)]
+        later_database_path: PathBuf,
+    },
+    /// Interactive shell for stepping through the fork
+    Repl,
+    /// Reproduce known issues
+    #[command(subcommand)]
+    Diagnose(DiagnoseCommand),
+    /// Stress test pool operations on the fork
+    #[command(subcommand)]
+    Stress(StressCommand),
+}
+
+#[derive(Subcommand)]
+enum InspectCommand {
+    /// Pool and validator state
+    State,
+    /// Supplier NFTs with their LSU and XRD claims
+    Nfts,
+    /// Configured pools with their liquidity and LSU locked
+    Pools,
+    /// Role protected methods of the Gable blueprint with the badge used to call them
+    Methods,
+}
+
+#[derive(Subcommand)]
+enum FixtureCommand {
+    /// Write supplier NFTs and pool state to csv files
+    Extract,
+}
+
+#[derive(Subcommand)]
+enum DiagnoseCommand {
+    /// Withdraw the last NFT of every supplier group
+    GroupDeletion,
+}
+
+#[derive(Subcommand)]
+enum StressCommand {
+    /// Flash loans of varying sizes, with repayment shortfalls, nested and without owner liquidity
+    FlashLoans,
+}
+
+fn parse_withdrawal_mode(name: &str) -> Result<WithdrawalMode, String> {
+    WithdrawalMode::from_name(name).ok_or_else(|| {
+        let names = WithdrawalMode::ALL.iter().map(|mode| mode.name()).collect::<Vec<_>>();
+        format!("unknown withdrawal mode `{}`, expected one of: {}", name, names.join(", "))
+    })
+}
+
+fn parse_scenario(name: &str) -> Result<Scenario, String> {
+    Scenario::from_name(name).ok_or_else(|| {
+        let names = Scenario::ALL.iter().map(|scenario| scenario.name()).collect::<Vec<_>>();
+        format!("unknown scenario `{}`, expected one of: {}", name, names.join(", "))
+    })
+}
+
+// a number in [0, 1], NaN is rejected too
+fn parse_probability(value: &str) -> Result<f64, String> {
+    let probability: f64 = value.parse().map_err(|_| format!("invalid number `{}`", value))?;
+    if !(0.0..=1.0).contains(&probability) {
+        return Err(format!("`{}` is not between 0 and 1", value));
+    }
+    Ok(probability)
+}
+
+fn parse_decimal(amount: &str) -> Result<Decimal, String> {
+    Decimal::from_str(amount).map_err(|error| format!("invalid amount `{}`: {:?}", amount, error))
+}
+
+fn parse_interest_rate_change(change: &str) -> Result<ParameterChange, String> {
+    let (day, interest_rate) = change.split_once('=').ok_or_else(|| format!("expected `day=rate`, got `{}`", change))?;
+    Ok(ParameterChange {
+        day: day.parse().map_err(|_| format!("invalid day `{}`", day))?,
+        parameter: Parameter::InterestRate(parse_decimal(interest_rate)?),
+    })
+}
+
+fn parse_box_size_change(change: &str) -> Result<ParameterChange, String> {
+    let (day, box_size) = change.split_once('=').ok_or_else(|| format!("expected `day=size`, got `{}`", change))?;
+    Ok(ParameterChange {
+        day: day.parse().map_err(|_| format!("invalid day `{}`", day))?,
+        parameter: Parameter::BoxSize(box_size.parse().map_err(|_| format!("invalid box size `{}`", box_size))?),
+    })
+}
+
+// `<resource address>:<local id>`, arguments are parsed before the network is known, so the address is accepted on any network
+fn parse_proof(proof: &str) -> Result<NonFungibleGlobalId, String> {
+    let (address, local_id) = proof.split_once(':').ok_or_else(|| format!("expected `<resource address>:<local id>`, got `{}`", proof))?;
+    let (_hrp, _entity_type, bytes) =
+        AddressBech32Decoder::validate_and_decode_ignore_hrp(address).map_err(|error| format!("invalid resource address `{}`: {:?}", address, error))?;
+    let resource_address = ResourceAddress::try_from(bytes.as_slice()).map_err(|_| format!("`{}` is not a resource address", address))?;
+    let local_id = NonFungibleLocalId::from_str(local_id).map_err(|error| format!("invalid local id `{}`: {:?}", local_id, error))?;
+    Ok(NonFungibleGlobalId::new(resource_address, local_id))
+}
+
+fn parse_variable(variable: &str) -> Result<(String, String), String> {
+    variable
+        .split_once('=')
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected `name=value`, got `{}`", variable))
+}
+
+fn capture_state(checkpoint: &str) -> StateView {
+    if checkpoint == "snapshot" {
+        return execute_within_environment(|mut simulation| StateView::capture(&mut simulation));
+    }
+    let checkpoint = Checkpoint::load(&PathBuf::from(checkpoint)).unwrap_or_else(|error| panic!("{}", error));
+    StateView::capture(&mut checkpoint.resume())
+}
+
+fn run_scenario(scenario: Scenario, config: &ScenarioConfig, checkpoint: Option<&Checkpoint>) -> ScenarioResult {
+    match checkpoint {
+        Some(checkpoint) => scenario.run(&mut checkpoint.resume(), config),
+        None => execute_within_environment(|mut simulation| scenario.run(&mut simulation, config)),
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Some(database_path) = cli.database_path.clone() {
+        set_database_path(database_path);
+    }
+    let profile = match &cli.profile {
+        Some(path) => EnvironmentProfile::load(path).unwrap_or_else(|error| panic!("{}", error)),
+        None => EnvironmentProfile::default(),
+    };
+    let profile = match &cli.pools {
+        Some(path) => EnvironmentProfile { pools: PoolRegistry::load(path).unwrap_or_else(|error| panic!("{}", error)), ..profile },
+        None => profile,
+    };
+    set_environment(profile.environment(cli.pool.as_deref()).unwrap_or_else(|error| panic!("{}", error)));
+    if let Some(state_version) = cli.state_version {
+        set_state_version(state_version);
+    }
+    // the report only reads csv files, which carry the metadata of the runs that wrote them
+    if !matches!(cli.command, Command::Report) {
+        snapshot_metadata().print();
+    }
+    std::fs::create_dir_all(&cli.output_dir).unwrap();
+    let checkpoint = cli.resume.as_ref().map(|path| Checkpoint::load(path).unwrap_or_else(|error| panic!("{}", error)));
+    let first_day = checkpoint.as_ref().map(|checkpoint| checkpoint.day + 1).unwrap_or(1);
+    if let Some(checkpoint) = &checkpoint {
+        // a checkpoint holds the state of one scenario, so `compare` can't start all of them from it
+        let scenario = match &cli.command {
+            Command::Run { scenario }
+            | Command::Montecarlo { scenario, .. }
+            | Command::Estimate { scenario, .. }
+            | Command::Withdrawals { scenario }
+            | Command::Sweep { scenario, .. } => Some(*scenario),
+            Command::Compare => panic!("`compare` runs every scenario and can't be resumed from a checkpoint"),
+            _ => None,
+        };
+        if let Some(scenario) = scenario {
+            checkpoint.validate(scenario).unwrap_or_else(|error| panic!("{}", error));
+        }
+        println!("Resuming from checkpoint `{}` at the end of day {}", checkpoint.label, checkpoint.day);
+    }
+    let config = ScenarioConfig {
+        days: cli.days,
+        seed: cli.seed,
+        output_dir: cli.output_dir.clone(),
+        first_day,
+        checkpoint_days: cli.checkpoint_days.clone(),
+        auth_mode: if cli.auth { AuthMode::Enabled } else { AuthMode::Disabled },
+        collect_events: cli.events,
+        activity: PoolActivity {
+            daily_lsu_deposit: cli.daily_lsu_deposit.unwrap_or_default(),
+            daily_flash_loans: cli.daily_flash_loans.clone(),
+        },
+        parameter_changes: cli.interest_rate_changes.iter().chain(&cli.box_size_changes).cloned().collect(),
+        timing: match cli.reward_arrivals {
+            Some(reward_arrivals_per_day) => Timing::Events { reward_arrivals_per_day },
+            None => Timing::Daily,
+        },
+        holders: HolderBehaviour {
+            check_in_probability: cli.check_in_probability,
+            min_claim_xrd: cli.min_claim_xrd.unwrap_or_default(),
+            whale_lsu: cli.whale_lsu,
+            inactive_share: cli.inactive_share,
+        },
+        withdrawal_mode: cli.withdrawal_mode,
+        ..Default::default()
+    };
+    if checkpoint.is_some() || !config.checkpoint_days.is_empty() {
+        let withdrawal_modes = match &cli.command {
+            Command::Withdrawals { .. } => WithdrawalMode::ALL.to_vec(),
+            _ => vec![config.withdrawal_mode],
+        };
+        for withdrawal_mode in withdrawal_modes {
+            ScenarioConfig { withdrawal_mode, ..config.clone() }.checkpoint_support().unwrap_or_else(|error| panic!("{}", error));
+        }
+    }
+
+    match cli.command {
+        Command::Run { scenario } => {
+            let result = run_scenario(scenario, &config, checkpoint.as_ref());
+            result.write_csv(&config.output_dir);
+            result.print_redemption_summary();
+            result.unlock_pipeline.print_summary();
+            result.holder_deferrals.print_summary();
+            result.fees.print_summary();
+            if config.collect_events {
+                result.events.print_summary();
+            }
+        }
+        Command::Compare => {
+            let results = Scenario::ALL
+                .iter()
+                .map(|scenario| run_scenario(*scenario, &config, checkpoint.as_ref()))
+                .collect::<Vec<_>>();
+            for result in &results {
+                result.write_csv(&config.output_dir);
+            }
+            let comparison = Comparison { results };
+            comparison.write_csv(&config.output_dir);
+            comparison.print();
+        }
+        Command::Montecarlo { scenario, runs } => {
+            let first_seed = cli.seed.unwrap_or(0);
+            let mut summary = MonteCarloSummary { scenario, runs: Vec::new() };
+            for seed in first_seed..first_seed + runs {
+                let config = ScenarioConfig {
+                    days: cli.days,
+                    seed: Some(seed),
+                    output_dir: cli.output_dir.clone(),
+                    first_day,
+                    auth_mode: config.auth_mode,
+                    activity: config.activity.clone(),
+                    parameter_changes: config.parameter_changes.clone(),
+                    timing: config.timing,
+                    holders: config.holders.clone(),
+                    withdrawal_mode: config.withdrawal_mode,
+                    ..Default::default()
+                };
+                let result = run_scenario(scenario, &config, checkpoint.as_ref());
+                summary.runs.push((seed, result.days_to_recovery));
+            }
+            summary.write_csv(&config.output_dir);
+            summary.print();
+        }
+        Command::Inspect(InspectCommand::State) => {
+            execute_within_environment(|mut simulation| inspect::print_state(&mut simulation));
+        }
+        Command::Inspect(InspectCommand::Nfts) => {
+            execute_within_environment(|mut simulation| inspect::print_nfts(&mut simulation));
+        }
+        Command::Inspect(InspectCommand::Pools) => {
+            for pool in &profile.pools.pools {
+                execute_within_pool(pool, |mut simulation| inspect::print_pool_summary(&mut simulation, &pool.name));
+            }
+        }
+        Command::Inspect(InspectCommand::Methods) => {
+            execute_within_environment(|mut simulation| inspect::print_protected_methods(&mut simulation));
+        }
+        Command::Fixture(FixtureCommand::Extract) => {
+            execute_within_environment(|mut simulation| inspect::extract_fixture(&mut simulation, &config.output_dir));
+        }
+        Command::Report => {
+            report::print_report(&config.output_dir);
+        }
+        Command::Manifest { paths, variables, proofs } => {
+            if !proofs.is_empty() && config.auth_mode == AuthMode::Disabled {
+                panic!("`--proof` is used only with `--auth`");
+            }
+            let variables = variables.into_iter().collect::<IndexMap<_, _>>();
+            let proofs = proofs.into_iter().collect::<BTreeSet<_>>();
+            execute_within_environment(|mut simulation| {
+                simulation.auth_mode = config.auth_mode;
+                for path in &paths {
+                    println!("Executing {}", path.display());
+                    let manifest = match manifests::load_manifest(&simulation, path, &variables) {
+                        Ok(manifest) => manifest,
+                        Err(error) => {
+                            println!("-- {}", error);
+                            continue;
+                        }
+                    };
+                    let operation = path.file_stem().unwrap().to_string_lossy().to_string();
+                    let receipt = if simulation.auth_mode == AuthMode::Enabled {
+                        let mut proofs = proofs.clone();
+                        proofs.extend(simulation.signer_proofs());
+                        simulation.execute_manifest_with_auth(&operation, manifest, proofs)
+                    } else {
+                        simulation.execute_manifest(&operation, manifest)
+                    };
+                    print_receipt(&receipt);
+                }
+            });
+        }
+        Command::Estimate { scenario, compare } => {
+            let input = match &checkpoint {
+                Some(checkpoint) => EstimatorInput::capture(&mut checkpoint.resume(), scenario, &config),
+                None => execute_within_environment(|mut simulation| EstimatorInput::capture(&mut simulation, scenario, &config)),
+            };
+            let recovery_estimate = estimate(&input, scenario, &config);
+            recovery_estimate.print();
+            if compare {
+                let result = run_scenario(scenario, &config, checkpoint.as_ref());
+                result.write_csv(&config.output_dir);
+                recovery_estimate.compare(&result).print();
+            }
+        }
+        Command::Withdrawals { scenario } => {
+            let mut outcomes = Vec::new();
+            for withdrawal_mode in WithdrawalMode::ALL {
+                // both modes get the same budget, pro-rata payouts can't use owner liquidity
+                let config = ScenarioConfig { withdrawal_mode, pool_liquidity_budget: true, ..config.clone() };
+                let result = run_scenario(scenario, &config, checkpoint.as_ref());
+                result.withdrawals.write_csv(&config.output_dir.join(format!("{}_{}_withdrawals.csv", scenario.name(), withdrawal_mode.name())));
+                outcomes.push((withdrawal_mode, result.days_to_recovery, result.withdrawals.fairness(withdrawal_mode, &result.fees)));
+            }
+            WithdrawalFairness::print_header();
+            for (withdrawal_mode, _days, fairness) in &outcomes {
+                fairness.print(*withdrawal_mode);
+            }
+            WithdrawalFairness::print_notes();
+            for (withdrawal_mode, days, _fairness) in &outcomes {
+                match days {
+                    Some(days) => println!("-- {}: recovered in {} days", withdrawal_mode.name(), days),
+                    None => println!("-- {}: not recovered", withdrawal_mode.name()),
+                }
+            }
+        }
+        Command::Sweep { scenario, rewards, epochs_per_day, held_back, lsu_deposits, jobs } => {
+            let ranges = SweepRanges {
+                daily_rewards: rewards,
+                epochs_per_day,
+                held_back_nfts: if held_back.is_empty() { vec![scenario.default_held_back_nfts()] } else { held_back },
+                daily_lsu_deposits: if lsu_deposits.is_empty() { vec![config.activity.daily_lsu_deposit] } else { lsu_deposits },
+            };
+            // the same seed for every point, so only the swept parameters differ
+            let config = ScenarioConfig { seed: Some(cli.seed.unwrap_or(0)), ..config.clone() };
+            let result = sweep::run(scenario, &config, &ranges, jobs, checkpoint.as_ref());
+            result.print();
+            result.write_csv(&config.output_dir);
+            result.write_heatmaps(&config.output_dir);
+        }
+        Command::Diff { from, to } => {
+            state_diff::print_diff(&capture_state(&from), &capture_state(&to));
+        }
+        Command::Replay { transactions, later_database_path } => {
+            let transactions = replay::load_transactions(&transactions);
+            let replayed = execute_within_environment(|mut simulation| {
+                let outcomes = replay::replay(&mut simulation, &transactions);
+                let failed = outcomes.iter().filter(|outcome| outcome.error.is_some()).count();
+                println!("Replayed {} transactions, failed: {}", outcomes.len(), failed);
+                StateView::capture(&mut simulation)
+            });
+            let later = execute_within_database(later_database_path, |mut simulation| StateView::capture(&mut simulation));
+            let differences = replay::compare_with_later_snapshot(&replayed, &later);
+            if differences.is_empty() {
+                println!("Replayed Gable state matches the later snapshot");
+            } else {
+                println!("Replayed Gable state differs from the later snapshot:");
+                for difference in differences {
+                    println!("-- {}", difference);
+                }
+            }
+        }
+        Command::Repl => {
+            execute_within_environment(|mut simulation| repl::run(&mut simulation));
+        }
+        Command::Diagnose(DiagnoseCommand::GroupDeletion) => {
+            execute_within_environment(|mut simulation| diagnose_group_deletion(&mut simulation).print());
+        }
+        Command::Stress(StressCommand::FlashLoans) => {
+            execute_within_environment(|mut simulation| {
+                let cases = flash_loan_stress::default_cases(&mut simulation);
+                let outcomes = flash_loan_stress::run(&mut simulation, &cases);
+                flash_loan_stress::print_outcomes(&outcomes);
+                flash_loan_stress::write_csv(&outcomes, &config.output_dir.join("flash_loan_stress.csv"));
+            });
+        }
+    }
+}
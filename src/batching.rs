@@ -0,0 +1,79 @@
+use radix_engine::errors::{RejectionReason, RuntimeError, SystemModuleError};
+use radix_engine::transaction::*;
+use radix_engine_common::prelude::*;
+use radix_engine_derive::ScryptoSbor;
+
+// Plans `update_supplier_kvs` calls into transactions which stay under the execution cost unit limit.
+// Cost of a group is estimated from previous batches, split proportionally to number of nfts in each group.
+#[derive(Clone, Debug, Default, ScryptoSbor)]
+pub struct SupplierKvsBatching {
+    // estimated execution cost units of one `update_supplier_kvs` call per group
+    pub group_costs: IndexMap<u64, u64>,
+}
+
+impl SupplierKvsBatching {
+    // share of the execution cost unit limit a planned batch may use
+    pub const TARGET_LIMIT_USAGE: f64 = 0.8;
+
+    pub fn target_cost(&self) -> u64 {
+        (EXECUTION_COST_UNIT_LIMIT as f64 * Self::TARGET_LIMIT_USAGE) as u64
+    }
+
+    fn group_cost(&self, group: u64) -> u64 {
+        if let Some(cost) = self.group_costs.get(&group) {
+            return *cost;
+        }
+        if self.group_costs.is_empty() {
+            return 0;
+        }
+        self.group_costs.values().sum::<u64>() / self.group_costs.len() as u64
+    }
+
+    // groups are (group, number of nfts in the group), without estimates all groups are in one batch
+    pub fn plan(&self, groups: &[(u64, usize)]) -> Vec<Vec<u64>> {
+        let mut batches: Vec<Vec<u64>> = Vec::new();
+        let mut batch = Vec::new();
+        let mut batch_cost = 0;
+        for (group, _nfts) in groups {
+            let cost = self.group_cost(*group);
+            if !batch.is_empty() && batch_cost + cost > self.target_cost() {
+                batches.push(std::mem::take(&mut batch));
+                batch_cost = 0;
+            }
+            batch.push(*group);
+            batch_cost += cost;
+        }
+        if !batch.is_empty() {
+            batches.push(batch);
+        }
+        batches
+    }
+
+    // updates estimates with execution cost units of a successful batch
+    pub fn learn(&mut self, batch: &[(u64, usize)], cost_units: u32) {
+        let cost_units = cost_units as u64;
+        let weight = batch.iter().map(|(_group, nfts)| *nfts as u64 + 1).sum::<u64>();
+        for (group, nfts) in batch {
+            self.group_costs.insert(*group, cost_units * (*nfts as u64 + 1) / weight);
+        }
+    }
+}
+
+// transaction failed because it was too big, not because of the call itself
+pub fn is_limit_error(receipt: &TransactionReceiptV1) -> bool {
+    let error = match &receipt.result {
+        TransactionResult::Commit(commit) => match &commit.outcome {
+            TransactionOutcome::Failure(error) => error,
+            TransactionOutcome::Success(_) => return false,
+        },
+        TransactionResult::Reject(reject) => match &reject.reason {
+            RejectionReason::ErrorBeforeLoanAndDeferredCostsRepaid(error) => error,
+            _ => return false,
+        },
+        TransactionResult::Abort(_) => return false,
+    };
+    matches!(
+        error,
+        RuntimeError::SystemModuleError(SystemModuleError::CostingError(_) | SystemModuleError::TransactionLimitsError(_))
+    )
+}
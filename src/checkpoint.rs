@@ -0,0 +1,211 @@
+use std::path::Path;
+
+use radix_engine_common::prelude::*;
+use radix_engine_derive::ScryptoSbor;
+use substate_store_interface::interface::*;
+
+use crate::batching::SupplierKvsBatching;
+use crate::events::EventLog;
+use crate::fees::FeeLedger;
+use crate::gable_simulation::{GableSimulation, SimulationSnapshot};
+use crate::provenance::SnapshotMetadata;
+use crate::scenarios::Scenario;
+use crate::scheduler::Scheduler;
+use crate::unlock_pipeline::UnlockPipeline;
+
+// State of a simulation at the end of a given day, stored as changes on top of the snapshot database with the in-memory trackers.
+// State kept by a scenario run itself (pro-rata payouts, holder draws) is not part of it.
+#[derive(Clone)]
+pub struct Checkpoint {
+    // name of the scenario which saved the checkpoint
+    pub label: String,
+    pub day: u64,
+    // snapshot the simulation forked from, the checkpoint applies only on the same one
+    pub snapshot: SnapshotMetadata,
+    pub account: ComponentAddress,
+    pub state: SimulationSnapshot,
+}
+
+impl Checkpoint {
+    pub fn capture(simulation: &GableSimulation, label: &str, day: u64) -> Self {
+        Checkpoint {
+            label: label.to_string(),
+            day,
+            snapshot: crate::snapshot_metadata().clone(),
+            account: simulation.account,
+            state: simulation.snapshot(),
+        }
+    }
+
+    // fails if the checkpoint was saved by another scenario than `scenario`
+    pub fn validate(&self, scenario: Scenario) -> Result<(), String> {
+        if self.label != scenario.name() {
+            return Err(format!("Checkpoint `{}` can't resume the {} scenario", self.label, scenario.name()));
+        }
+        Ok(())
+    }
+
+    // fails if the checkpoint was saved on another network, pool or snapshot, the user-supplied state version is not compared
+    fn check_snapshot(&self) -> Result<(), String> {
+        let current = crate::snapshot_metadata();
+        if self.snapshot.network != current.network {
+            return Err(format!("Checkpoint `{}` was saved on network {}, not {}", self.label, self.snapshot.network, current.network));
+        }
+        if self.snapshot.pool != current.pool {
+            return Err(format!("Checkpoint `{}` was saved for pool `{}`, not `{}`", self.label, self.snapshot.pool, current.pool));
+        }
+        if (self.snapshot.epoch, self.snapshot.round, self.snapshot.gable_state_hash) != (current.epoch, current.round, current.gable_state_hash) {
+            return Err(format!(
+                "Checkpoint `{}` was saved on the snapshot at epoch {} round {} with gable state hash {}, not epoch {} round {} with {}",
+                self.label, self.snapshot.epoch, self.snapshot.round, self.snapshot.gable_state_hash, current.epoch, current.round, current.gable_state_hash
+            ));
+        }
+        Ok(())
+    }
+
+    // new simulation on a fresh fork of the snapshot database with the checkpoint state applied
+    pub fn resume<'a>(&self) -> GableSimulation<'a> {
+        let mut test_runner = crate::new_test_runner();
+        test_runner.substate_db_mut().commit(&self.state.database_updates);
+        let mut simulation = GableSimulation::with_account(test_runner, crate::get_pool(), self.account);
+        simulation.restore_trackers(&self.state);
+        simulation
+    }
+
+    pub fn save(&self, path: &Path) {
+        let file = CheckpointFile {
+            label: self.label.clone(),
+            day: self.day,
+            snapshot: self.snapshot.clone(),
+            account: self.account,
+            node_updates: self
+                .state
+                .database_updates
+                .node_updates
+                .iter()
+                .map(|(node_key, node_updates)| CheckpointNodeUpdates::from_node_updates(node_key, node_updates))
+                .collect(),
+            fees: self.state.fees.clone(),
+            supplier_kvs_batching: self.state.supplier_kvs_batching.clone(),
+            events: self.state.events.clone(),
+            unlock_pipeline: self.state.unlock_pipeline.clone(),
+            scheduler: self.state.scheduler.clone(),
+        };
+        std::fs::write(path, scrypto_encode(&file).unwrap()).unwrap();
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        let file: CheckpointFile = scrypto_decode(&bytes).map_err(|error| format!("{}: invalid checkpoint file: {:?}", path.display(), error))?;
+        let mut database_updates = DatabaseUpdates::default();
+        for node in file.node_updates {
+            let (node_key, node_updates) = node.into_node_updates();
+            database_updates.node_updates.insert(node_key, node_updates);
+        }
+        let checkpoint = Checkpoint {
+            label: file.label,
+            day: file.day,
+            snapshot: file.snapshot,
+            account: file.account,
+            state: SimulationSnapshot {
+                database_updates,
+                fees: file.fees,
+                supplier_kvs_batching: file.supplier_kvs_batching,
+                events: file.events,
+                unlock_pipeline: file.unlock_pipeline,
+                scheduler: file.scheduler,
+            },
+        };
+        checkpoint.check_snapshot().map_err(|error| format!("{}: {}", path.display(), error))?;
+        Ok(checkpoint)
+    }
+
+    pub fn file_name(label: &str, day: u64) -> String {
+        format!("{}_day_{}.checkpoint", label, day)
+    }
+}
+
+#[derive(ScryptoSbor)]
+struct CheckpointFile {
+    label: String,
+    day: u64,
+    snapshot: SnapshotMetadata,
+    account: ComponentAddress,
+    node_updates: Vec<CheckpointNodeUpdates>,
+    fees: FeeLedger,
+    supplier_kvs_batching: SupplierKvsBatching,
+    events: EventLog,
+    unlock_pipeline: UnlockPipeline,
+    scheduler: Scheduler,
+}
+
+#[derive(ScryptoSbor)]
+struct CheckpointNodeUpdates {
+    node_key: Vec<u8>,
+    partition_updates: Vec<(u8, CheckpointPartitionUpdates)>,
+}
+
+#[derive(ScryptoSbor)]
+enum CheckpointPartitionUpdates {
+    // None deletes the substate
+    Delta(Vec<(Vec<u8>, Option<Vec<u8>>)>),
+    Reset(Vec<(Vec<u8>, Vec<u8>)>),
+}
+
+impl CheckpointNodeUpdates {
+    fn from_node_updates(node_key: &DbNodeKey, node_updates: &NodeDatabaseUpdates) -> Self {
+        let partition_updates = node_updates
+            .partition_updates
+            .iter()
+            .map(|(partition_num, partition_updates)| {
+                let partition_updates = match partition_updates {
+                    PartitionDatabaseUpdates::Delta { substate_updates } => CheckpointPartitionUpdates::Delta(
+                        substate_updates
+                            .iter()
+                            .map(|(sort_key, update)| match update {
+                                DatabaseUpdate::Set(value) => (sort_key.0.clone(), Some(value.clone())),
+                                DatabaseUpdate::Delete => (sort_key.0.clone(), None),
+                            })
+                            .collect(),
+                    ),
+                    PartitionDatabaseUpdates::Reset { new_substate_values } => CheckpointPartitionUpdates::Reset(
+                        new_substate_values
+                            .iter()
+                            .map(|(sort_key, value)| (sort_key.0.clone(), value.clone()))
+                            .collect(),
+                    ),
+                };
+                (*partition_num, partition_updates)
+            })
+            .collect();
+        CheckpointNodeUpdates {
+            node_key: node_key.clone(),
+            partition_updates,
+        }
+    }
+
+    fn into_node_updates(self) -> (DbNodeKey, NodeDatabaseUpdates) {
+        let mut node_updates = NodeDatabaseUpdates::default();
+        for (partition_num, partition_updates) in self.partition_updates {
+            let partition_updates = match partition_updates {
+                CheckpointPartitionUpdates::Delta(substate_updates) => PartitionDatabaseUpdates::Delta {
+                    substate_updates: substate_updates
+                        .into_iter()
+                        .map(|(sort_key, value)| match value {
+                            Some(value) => (DbSortKey(sort_key), DatabaseUpdate::Set(value)),
+                            None => (DbSortKey(sort_key), DatabaseUpdate::Delete),
+                        })
+                        .collect(),
+                },
+                CheckpointPartitionUpdates::Reset(new_substate_values) => PartitionDatabaseUpdates::Reset {
+                    new_substate_values: new_substate_values
+                        .into_iter()
+                        .map(|(sort_key, value)| (DbSortKey(sort_key), value))
+                        .collect(),
+                },
+            };
+            node_updates.partition_updates.insert(partition_num, partition_updates);
+        }
+        (self.node_key, node_updates)
+    }
+}
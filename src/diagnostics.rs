@@ -0,0 +1,108 @@
+use radix_engine_common::prelude::*;
+use radix_engine_interface::macros::dec;
+
+use crate::gable_simulation::{receipt_error, GableSimulation};
+
+pub struct GroupDeletionOutcome {
+    // supplier_partitioned_kvs key
+    pub group: u64,
+    // nft which would empty the group when withdrawn
+    pub last_nft: NonFungibleLocalId,
+    pub lsu: Decimal,
+    pub xrd_claim: Decimal,
+    // nfts of the group withdrawn before the last one and errors of the ones that failed
+    pub withdrawn_before_last: usize,
+    pub errors_before_last: Vec<(NonFungibleLocalId, String)>,
+    // engine error of the last withdrawal, None if it succeeded
+    pub error: Option<String>,
+}
+
+pub struct GroupDeletionReport {
+    pub outcomes: Vec<GroupDeletionOutcome>,
+}
+
+impl GroupDeletionReport {
+    // the last withdrawal failed although the group was emptied before it
+    pub fn affected_groups(&self) -> Vec<&GroupDeletionOutcome> {
+        self.outcomes.iter().filter(|outcome| outcome.error.is_some() && outcome.errors_before_last.is_empty()).collect()
+    }
+
+    // the last withdrawal failed, but the group was never emptied because earlier withdrawals failed too
+    pub fn inconclusive_groups(&self) -> Vec<&GroupDeletionOutcome> {
+        self.outcomes.iter().filter(|outcome| outcome.error.is_some() && !outcome.errors_before_last.is_empty()).collect()
+    }
+
+    // LSU kept in the contract by skipping the last nft of every group
+    pub fn stranded_lsu(&self) -> Decimal {
+        self.outcomes.iter().fold(dec!(0), |sum, outcome| sum + outcome.lsu)
+    }
+
+    pub fn stranded_lsu_in_affected_groups(&self) -> Decimal {
+        self.affected_groups().iter().fold(dec!(0), |sum, outcome| sum + outcome.lsu)
+    }
+
+    pub fn print(&self) {
+        for outcome in &self.outcomes {
+            match &outcome.error {
+                None => println!("-- Group {}: last NFT {:?} with LSU: {} withdrawn successfully", outcome.group, outcome.last_nft, outcome.lsu),
+                Some(error) => println!("-- Group {}: last NFT {:?} with LSU: {} failed: {}", outcome.group, outcome.last_nft, outcome.lsu, error),
+            }
+            for (nft, error) in &outcome.errors_before_last {
+                println!("---- NFT {:?} of group {} failed before the last one: {}", nft, outcome.group, error);
+            }
+        }
+        println!(
+            "Groups: {}, affected by group deletion issue: {}, inconclusive (earlier NFTs of the group failed): {}",
+            self.outcomes.len(),
+            self.affected_groups().len(),
+            self.inconclusive_groups().len()
+        );
+        println!("LSU stranded by workaround: {}, in affected groups: {}", self.stranded_lsu(), self.stranded_lsu_in_affected_groups());
+    }
+}
+
+// Withdraws every nft of every group, the one with lowest LSU last, and records what happens when a group gets emptied.
+// Owner liquidity is funded upfront so liquidity is never the reason of a failure.
+pub fn diagnose_group_deletion(simulation: &mut GableSimulation) -> GroupDeletionReport {
+    // claim groups are recalculated first, as scenarios do before withdrawing
+    simulation.update_supplier_kvs();
+    let nft_groups = simulation.get_supplier_partitioned_kvs();
+    let last_nfts = simulation.get_last_nft_in_each_group();
+
+    let total_claims = simulation
+        .get_lsu_claims(false)
+        .values()
+        .fold(dec!(0), |sum, (_lsu, xrd)| sum + *xrd);
+    simulation.fund_owner_liquidity(total_claims);
+
+    let mut outcomes = Vec::new();
+    for (group, (last_nft, (lsu, xrd_claim))) in last_nfts {
+        let mut withdrawn_before_last = 0;
+        let mut errors_before_last = Vec::new();
+        for (nft, _amounts) in &nft_groups[&group] {
+            if *nft == last_nft {
+                continue;
+            }
+            simulation.create_nft_duplicate(simulation.account, nft.clone());
+            let receipt = simulation.try_withdraw_lsu(nft.clone());
+            match receipt_error(&receipt) {
+                None => withdrawn_before_last += 1,
+                Some(error) => errors_before_last.push((nft.clone(), error)),
+            }
+        }
+
+        simulation.create_nft_duplicate(simulation.account, last_nft.clone());
+        let receipt = simulation.try_withdraw_lsu(last_nft.clone());
+        outcomes.push(GroupDeletionOutcome {
+            group,
+            last_nft,
+            lsu,
+            xrd_claim,
+            withdrawn_before_last,
+            errors_before_last,
+            error: receipt_error(&receipt),
+        });
+    }
+
+    GroupDeletionReport { outcomes }
+}
@@ -0,0 +1,106 @@
+use std::path::Path;
+
+use radix_engine_common::prelude::*;
+
+use crate::pools::{PoolConfig, PoolRegistry};
+
+// Network and pool all simulations of a run use.
+#[derive(Clone, Debug)]
+pub struct Environment {
+    pub network: NetworkDefinition,
+    pub pool: PoolConfig,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Environment { network: NetworkDefinition::mainnet(), pool: PoolConfig::gable_mainnet() }
+    }
+}
+
+// Network of the snapshot and pools deployed on it, mainnet with the Gable pool if no profile is given.
+#[derive(Clone, Debug)]
+pub struct EnvironmentProfile {
+    pub network: NetworkDefinition,
+    pub pools: PoolRegistry,
+}
+
+impl Default for EnvironmentProfile {
+    fn default() -> Self {
+        EnvironmentProfile { network: NetworkDefinition::mainnet(), pools: PoolRegistry::default() }
+    }
+}
+
+impl EnvironmentProfile {
+    // Reads a profile with the network at the top and a `[name]` section per pool, `#` starts a comment:
+    //   network = stokenet
+    //   [gable]
+    //   component = component_tdx_2_1...
+    //   validator = validator_tdx_2_1...
+    //   owner_account = account_tdx_2_1...
+    // `network` is one of mainnet, stokenet, localnet or simulator, other networks are set with `network_id`,
+    // `network_name` and `hrp_suffix`. Pool keys `owner_badge`, `lsu` and `validator_owner_badge` are optional.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        Self::parse(&text).map_err(|error| format!("{}: {}", path.display(), error))
+    }
+
+    // network keys come before the first section, the sections are read by `PoolRegistry`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut network = NetworkDefinition::mainnet();
+        let mut custom_network_id = false;
+        let mut custom_hrp_suffix = false;
+        let mut lines = text.lines().enumerate().peekable();
+        while let Some((index, line)) = lines.next_if(|(_, line)| !line.trim_start().starts_with('[')) {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {}: expected `key = value`", index + 1));
+            };
+            let (key, value) = (key.trim(), value.trim().to_string());
+            match key {
+                "network" => network = network_by_name(&value).ok_or_else(|| format!("line {}: unknown network `{}`", index + 1, value))?,
+                "network_id" => {
+                    network.id = value.parse().map_err(|_| format!("line {}: invalid network id `{}`", index + 1, value))?;
+                    custom_network_id = true;
+                }
+                "network_name" => network.logical_name = value,
+                "hrp_suffix" => {
+                    network.hrp_suffix = value;
+                    custom_hrp_suffix = true;
+                }
+                key => return Err(format!("line {}: unknown network key `{}`", index + 1, key)),
+            }
+        }
+        // addresses of another network would be decoded with the hrp of the named one
+        if custom_network_id && !custom_hrp_suffix {
+            return Err("`network_id` is set without `hrp_suffix`".to_string());
+        }
+        let pools = PoolRegistry::parse_lines(lines)?;
+        // the Gable pool is known only on mainnet
+        let pools = if pools.pools.is_empty() && network.id == NetworkDefinition::mainnet().id { PoolRegistry::default() } else { pools };
+        Ok(EnvironmentProfile { network, pools })
+    }
+
+    // the pool with given name, or the first one of the profile
+    pub fn environment(&self, pool: Option<&str>) -> Result<Environment, String> {
+        let selected = match pool {
+            Some(name) => self.pools.get(name).ok_or_else(|| format!("Unknown pool `{}`, configured pools: {}", name, self.pools.names().join(", ")))?,
+            None => self.pools.pools.first().ok_or_else(|| format!("No pool is configured for network {}", self.network.logical_name))?,
+        };
+        // addresses are checked against the network before any simulation is created
+        selected.decode(&AddressBech32Decoder::new(&self.network))?;
+        Ok(Environment { network: self.network.clone(), pool: selected.clone() })
+    }
+}
+
+pub fn network_by_name(name: &str) -> Option<NetworkDefinition> {
+    match name {
+        "mainnet" => Some(NetworkDefinition::mainnet()),
+        "stokenet" => Some(NetworkDefinition::stokenet()),
+        "localnet" => Some(NetworkDefinition::localnet()),
+        "simulator" => Some(NetworkDefinition::simulator()),
+        _ => None,
+    }
+}
@@ -0,0 +1,275 @@
+use radix_engine_common::prelude::*;
+use radix_engine_interface::macros::dec;
+
+use crate::gable_simulation::GableSimulation;
+use crate::scenarios::{Scenario, ScenarioConfig, ScenarioResult};
+
+// Pool state the estimate starts from, read once from the fork.
+#[derive(Clone, Debug)]
+pub struct EstimatorInput {
+    pub epoch: u64,
+    pub xrd_balance: Decimal,
+    pub owner_liquidity: Decimal,
+    pub owner_xrd_balance: Decimal,
+    pub lsu_locked: Decimal,
+    // LSU and XRD claims of nfts which have to be withdrawn for recovery
+    pub claims_lsu: Decimal,
+    pub claims_xrd: Decimal,
+    // LSU of all supplier nfts, rewards are shared by all of them pro rata
+    pub supplier_lsu: Decimal,
+    pub redemption_rate: Decimal,
+    pub owner_unlock_epochs: u64,
+    pub unstake_epochs: u64,
+    // XRD by epoch at which owner unlocks can be finished, converted with the redemption rate
+    pub pending_owner_unlocks: BTreeMap<u64, Decimal>,
+    // XRD by epoch at which unstakes can be claimed
+    pub pending_unstakes: BTreeMap<u64, Decimal>,
+}
+
+impl EstimatorInput {
+    pub fn capture(simulation: &mut GableSimulation, scenario: Scenario, config: &ScenarioConfig) -> Self {
+        let held_back_nfts = config.held_back_nfts.unwrap_or(scenario.default_held_back_nfts());
+        let mut all_claims = simulation.get_lsu_claims(false);
+        all_claims.sort_by(|_, v1, _, v2| v2.0.cmp(&v1.0));
+        let mut claims = simulation.get_lsu_claims(true);
+        for (nft, _amounts) in all_claims.iter().take(held_back_nfts) {
+            claims.remove(nft);
+        }
+
+        let redemption_rate = simulation.get_lsu_redemption_rate();
+        let consensus_manager_config = simulation.consensus_manager_config();
+        let mut pending_owner_unlocks = BTreeMap::new();
+        for (epoch, lsu) in simulation.get_pending_owner_unlocks() {
+            *pending_owner_unlocks.entry(epoch.number()).or_insert(dec!(0)) += lsu * redemption_rate;
+        }
+        let mut pending_unstakes = BTreeMap::new();
+        for (epoch, xrd) in simulation.get_pending_unstakes() {
+            *pending_unstakes.entry(epoch.number()).or_insert(dec!(0)) += xrd;
+        }
+
+        EstimatorInput {
+            epoch: simulation.test_runner.get_current_epoch().number(),
+            xrd_balance: simulation.get_xrd_balance(),
+            owner_liquidity: simulation.get_owner_liqudity(),
+            owner_xrd_balance: simulation.get_owner_xrd_balance(),
+            lsu_locked: simulation.get_lsu_balance(),
+            claims_lsu: claims.values().fold(dec!(0), |sum, (lsu, _xrd)| sum + *lsu),
+            claims_xrd: claims.values().fold(dec!(0), |sum, (_lsu, xrd)| sum + *xrd),
+            supplier_lsu: all_claims.values().fold(dec!(0), |sum, (lsu, _xrd)| sum + *lsu),
+            redemption_rate,
+            owner_unlock_epochs: consensus_manager_config.num_owner_stake_units_unlock_epochs,
+            unstake_epochs: consensus_manager_config.num_unstake_epochs,
+            pending_owner_unlocks,
+            pending_unstakes,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EstimatedDay {
+    pub day: u64,
+    pub epoch: u64,
+    pub lsu_locked: Decimal,
+    pub rewards: Decimal,
+    pub pool_liquidity: Decimal,
+    // XRD claims of nfts which are still to be withdrawn
+    pub remaining_claims: Decimal,
+}
+
+pub struct RecoveryEstimate {
+    pub scenario: Scenario,
+    pub days: Vec<EstimatedDay>,
+    pub days_to_recovery: Option<u64>,
+}
+
+// XRD arriving as pool liquidity, in closed form over days `n` of the estimate (day 1 is `config.first_day`).
+// A reward staked before the epochs of day `n` are added arrives on day `n + reward_delay_days`, owner stake
+// added after them on day `n + stake_delay_days`.
+struct Inflow {
+    initial_liquidity: Decimal,
+    daily_reward: Decimal,
+    reward_delay_days: u64,
+    stake_delay_days: u64,
+    // pending owner unlocks and unstakes of the snapshot by day of arrival
+    pending: Vec<(u64, Decimal)>,
+    // owner XRD staked on the last day of step 1 of the perfect scenario
+    owner_stake: Option<(u64, Decimal)>,
+    // XRD of rewards recycled LSU bring per XRD paid out, 0 without recycling
+    recycled_share: Decimal,
+    // XRD value of all LSU which can be recycled
+    recycled_cap: Decimal,
+}
+
+impl Inflow {
+    // liquidity without recycling: initial liquidity, pending arrivals, owner stake and linear daily rewards after the delay
+    fn base(&self, n: u64) -> Decimal {
+        if n == 0 {
+            return dec!(0);
+        }
+        let pending = self.pending.iter().filter(|(day, _xrd)| *day <= n).fold(dec!(0), |sum, (_day, xrd)| sum + *xrd);
+        let owner_stake = match self.owner_stake {
+            Some((day, xrd)) if day + self.stake_delay_days <= n => xrd,
+            _ => dec!(0),
+        };
+        self.initial_liquidity + pending + owner_stake + self.daily_reward * Decimal::from(n.saturating_sub(self.reward_delay_days))
+    }
+
+    // every XRD paid out brings `recycled_share` XRD of rewards `stake_delay_days` later, so
+    // S(n) = B(n) + min(q·B(n − d) + q²·B(n − 2d) + ..., cap) with B the liquidity without recycling
+    fn cumulative(&self, n: u64) -> Decimal {
+        let delay = self.stake_delay_days.max(1);
+        let mut recycled = dec!(0);
+        let mut factor = self.recycled_share;
+        let mut generation = 1;
+        // B is nondecreasing, so terms stop once B is zero
+        while !factor.is_zero() && generation * delay < n && recycled < self.recycled_cap {
+            let base = self.base(n - generation * delay);
+            if base.is_zero() {
+                break;
+            }
+            recycled += factor * base;
+            factor *= self.recycled_share;
+            generation += 1;
+        }
+        self.base(n) + recycled.min(self.recycled_cap)
+    }
+}
+
+fn days_for_epochs(epochs: u64, epochs_per_day: u64) -> u64 {
+    (epochs + epochs_per_day - 1) / epochs_per_day
+}
+
+// Closed form of the scenario on pool aggregates instead of individual nfts: rewards arrive linearly after the
+// owner unlock and unstake delays, a share of them is credited to the claims pro rata to LSU (taken constant at
+// its initial value), and claims are drained once arrived liquidity covers them, as if nfts could be withdrawn in parts.
+// The perfect scenario additionally stakes owner XRD after step 1 and recycles withdrawn LSU as rewards, which has
+// no inverse, so the drain day is found by bisection of the cumulative liquidity.
+pub fn estimate(input: &EstimatorInput, scenario: Scenario, config: &ScenarioConfig) -> RecoveryEstimate {
+    let epochs_per_day = config.epochs_per_day.max(1);
+    let unlock_days = days_for_epochs(input.owner_unlock_epochs, epochs_per_day);
+    let unstake_days = days_for_epochs(input.unstake_epochs, epochs_per_day);
+    let arrival_day = |epoch: u64| days_for_epochs(epoch.saturating_sub(input.epoch), epochs_per_day).max(1);
+    let mut pending: Vec<(u64, Decimal)> = input.pending_unstakes.iter().map(|(epoch, xrd)| (arrival_day(*epoch), *xrd)).collect();
+    pending.extend(input.pending_owner_unlocks.iter().map(|(epoch, xrd)| (arrival_day(*epoch) + unstake_days, *xrd)));
+    let mut inflow = Inflow {
+        initial_liquidity: input.xrd_balance - input.owner_liquidity,
+        daily_reward: config.daily_reward,
+        reward_delay_days: unlock_days.max(1) - 1 + unstake_days,
+        stake_delay_days: unlock_days + unstake_days,
+        pending,
+        owner_stake: None,
+        recycled_share: dec!(0),
+        recycled_cap: dec!(0),
+    };
+    let days = (config.days + 1).saturating_sub(config.first_day);
+
+    // step 1 of the perfect scenario ends the first day the pool has liquidity, at the latest on day 100,
+    // then the owner account keeps 100 XRD and stakes the rest
+    let mut first_withdrawal = 1;
+    if scenario == Scenario::Perfect && input.owner_liquidity > dec!(0) {
+        let last_day = 100u64.saturating_sub(config.first_day) + 1;
+        let recovered = (1..=last_day).find(|n| inflow.base(*n) > dec!(0));
+        let owner_xrd = match recovered {
+            Some(_) => input.owner_xrd_balance + input.owner_liquidity,
+            None => input.owner_xrd_balance,
+        };
+        let step_1_end = recovered.unwrap_or(last_day);
+        inflow.owner_stake = Some((step_1_end, (owner_xrd - dec!(100)).max(dec!(0))));
+        first_withdrawal = step_1_end + 1;
+    }
+    if scenario == Scenario::Perfect && input.claims_xrd > dec!(0) {
+        inflow.recycled_cap = input.claims_lsu * input.redemption_rate;
+        inflow.recycled_share = inflow.recycled_cap / input.claims_xrd;
+    }
+
+    // claims grow by share `s` of rewards, so they are covered once X0 + (1 − s)·(S(n) − X0) ≥ C0
+    let claims_share = if input.supplier_lsu > dec!(0) { input.claims_lsu / input.supplier_lsu } else { dec!(0) };
+    let claims = |n: u64| input.claims_xrd + claims_share * (inflow.cumulative(n) - inflow.initial_liquidity).max(dec!(0));
+    let paid = |n: u64| if n < first_withdrawal { dec!(0) } else { claims(n).min(inflow.cumulative(n)) };
+    let covered = |n: u64| n >= first_withdrawal && inflow.cumulative(n) >= claims(n);
+
+    // the scenario notices there is nothing left to withdraw the day after the last withdrawal
+    let drain_day = if covered(days) {
+        let (mut low, mut high) = (0, days);
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+            if covered(middle) { high = middle } else { low = middle }
+        }
+        Some(high)
+    } else {
+        None
+    };
+    let recovery_day = drain_day.map(|n| n + 1).filter(|n| *n <= days);
+
+    let mut estimate = RecoveryEstimate {
+        scenario,
+        days: Vec::new(),
+        days_to_recovery: recovery_day.map(|n| config.first_day + n - 1),
+    };
+    // values before the withdrawals of the day, like `DayRecord`
+    for n in 1..=recovery_day.unwrap_or(days) {
+        let paid_before = paid(n - 1);
+        let withdrawn_share = if claims(n - 1).is_zero() { dec!(1) } else { paid_before / claims(n - 1) };
+        let previous = if n == 1 { inflow.initial_liquidity } else { inflow.cumulative(n - 1) };
+        estimate.days.push(EstimatedDay {
+            day: config.first_day + n - 1,
+            epoch: input.epoch + n * epochs_per_day,
+            lsu_locked: input.lsu_locked - input.claims_lsu * withdrawn_share,
+            rewards: inflow.cumulative(n) - previous,
+            pool_liquidity: inflow.cumulative(n) - paid_before,
+            remaining_claims: claims(n) - paid_before,
+        });
+    }
+    estimate
+}
+
+pub struct EstimateComparison {
+    pub estimated_days: Option<u64>,
+    pub actual_days: Option<u64>,
+    // mean of relative differences of LSU locked on days present in both
+    pub lsu_locked_error: f64,
+    pub max_lsu_locked_error: f64,
+}
+
+impl RecoveryEstimate {
+    pub fn print(&self) {
+        for record in &self.days {
+            println!("-- Day: {}, epoch {}, LSU locked: {}, rewards: {}, pool liquidity: {}, remaining claims: {}", record.day, record.epoch, record.lsu_locked, record.rewards, record.pool_liquidity, record.remaining_claims);
+        }
+        match self.days_to_recovery {
+            Some(days) => println!("Scenario {}: estimated days to recovery: {}", self.scenario.name(), days),
+            None => println!("Scenario {}: not recovered within {} estimated days", self.scenario.name(), self.days.len()),
+        }
+    }
+
+    pub fn compare(&self, result: &ScenarioResult) -> EstimateComparison {
+        let mut errors = Vec::new();
+        for actual in &result.days {
+            let Some(estimated) = self.days.iter().find(|record| record.day == actual.day) else {
+                continue;
+            };
+            if actual.lsu_locked.is_zero() {
+                continue;
+            }
+            let difference = (estimated.lsu_locked - actual.lsu_locked).checked_abs().unwrap_or_default() / actual.lsu_locked;
+            errors.push(difference.to_string().parse::<f64>().unwrap_or_default());
+        }
+        EstimateComparison {
+            estimated_days: self.days_to_recovery,
+            actual_days: result.days_to_recovery,
+            lsu_locked_error: if errors.is_empty() { 0.0 } else { errors.iter().sum::<f64>() / errors.len() as f64 },
+            max_lsu_locked_error: errors.iter().cloned().fold(0.0, f64::max),
+        }
+    }
+}
+
+impl EstimateComparison {
+    pub fn print(&self) {
+        let days = |days: Option<u64>| days.map(|days| days.to_string()).unwrap_or("-".to_string());
+        println!("Days to recovery estimated: {}, on the fork: {}", days(self.estimated_days), days(self.actual_days));
+        if let (Some(estimated), Some(actual)) = (self.estimated_days, self.actual_days) {
+            println!("-- Error: {} days ({:.1}%)", estimated as i64 - actual as i64, (estimated as f64 - actual as f64) / actual as f64 * 100.0);
+        }
+        println!("-- LSU locked error, mean: {:.2}%, max: {:.2}%", self.lsu_locked_error * 100.0, self.max_lsu_locked_error * 100.0);
+    }
+}
@@ -0,0 +1,145 @@
+use std::path::Path;
+
+use radix_engine::transaction::*;
+use radix_engine_common::prelude::*;
+use radix_engine_derive::ScryptoSbor;
+
+use crate::gable_simulation::GableSimulationTestRunner;
+use crate::provenance;
+
+#[derive(Clone, Debug, ScryptoSbor)]
+pub struct EventRecord {
+    pub day: u64,
+    pub epoch: u64,
+    // index of the transaction in the simulation, the same as index of its fee record
+    pub transaction: usize,
+    pub operation: String,
+    pub emitter: Emitter,
+    pub name: String,
+    // payload rendered with the event schema
+    pub payload: String,
+    pub raw_payload: Vec<u8>,
+}
+
+impl EventRecord {
+    pub fn emitter_node_id(&self) -> Option<NodeId> {
+        match &self.emitter {
+            Emitter::Method(node_id, _module_id) => Some(*node_id),
+            Emitter::Function(_blueprint_id) => None,
+        }
+    }
+
+    pub fn emitter_display(&self, encoder: &AddressBech32Encoder) -> String {
+        match &self.emitter {
+            Emitter::Method(node_id, module_id) => format!("{}:{:?}", encoder.encode(node_id.as_bytes()).unwrap(), module_id),
+            Emitter::Function(blueprint_id) => format!("{}::{}", encoder.encode(blueprint_id.package_address.as_node_id().as_bytes()).unwrap(), blueprint_id.blueprint_name),
+        }
+    }
+
+    // decodes payload into a known event type, e.g. `fungible_vault::WithdrawEvent`
+    pub fn decode<T: ScryptoDecode>(&self) -> Option<T> {
+        scrypto_decode(&self.raw_payload).ok()
+    }
+}
+
+#[derive(Clone, Debug, Default, ScryptoSbor)]
+pub struct EventLog {
+    // events are collected only if enabled
+    pub enabled: bool,
+    pub records: Vec<EventRecord>,
+}
+
+impl EventLog {
+    pub fn collect(&mut self, test_runner: &mut GableSimulationTestRunner<'_>, day: u64, epoch: u64, transaction: usize, operation: &str, receipt: &TransactionReceiptV1) {
+        let TransactionResult::Commit(commit) = &receipt.result else {
+            return;
+        };
+        let encoder = AddressBech32Encoder::new(crate::network());
+        for (event_type_identifier, payload) in &commit.application_events {
+            let (type_id, schema) = test_runner.event_schema(event_type_identifier);
+            let schema = schema.into_latest();
+            let rendered = ScryptoRawPayload::new_from_valid_slice_with_checks(payload)
+                .map(|raw_payload| {
+                    raw_payload.to_string(ValueDisplayParameters::Annotated {
+                        display_context: ScryptoValueDisplayContext::with_optional_bech32(Some(&encoder)),
+                        schema: &schema,
+                        type_id,
+                        depth_limit: SCRYPTO_SBOR_V1_MAX_DEPTH,
+                    })
+                })
+                .unwrap_or_else(|_| format!("{:?}", payload));
+            self.records.push(EventRecord {
+                day,
+                epoch,
+                transaction,
+                operation: operation.to_string(),
+                emitter: event_type_identifier.0.clone(),
+                name: test_runner.event_name(event_type_identifier),
+                payload: rendered,
+                raw_payload: payload.clone(),
+            });
+        }
+    }
+
+    pub fn by_operation<'a>(&'a self, operation: &'a str) -> impl Iterator<Item = &'a EventRecord> {
+        self.records.iter().filter(move |record| record.operation == operation)
+    }
+
+    pub fn by_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a EventRecord> {
+        self.records.iter().filter(move |record| record.name == name)
+    }
+
+    pub fn by_emitter<'a>(&'a self, node_id: &'a NodeId) -> impl Iterator<Item = &'a EventRecord> {
+        self.records.iter().filter(move |record| record.emitter_node_id().as_ref() == Some(node_id))
+    }
+
+    pub fn by_transaction(&self, transaction: usize) -> impl Iterator<Item = &EventRecord> {
+        self.records.iter().filter(move |record| record.transaction == transaction)
+    }
+
+    // transactions (indices of fee ledger records) without an event of given name emitted by given node,
+    // including transactions which emitted no event at all
+    pub fn transactions_missing_event(&self, transactions: impl IntoIterator<Item = usize>, emitter: &NodeId, name: &str) -> Vec<usize> {
+        transactions
+            .into_iter()
+            .filter(|transaction| {
+                !self
+                    .by_transaction(*transaction)
+                    .any(|record| record.name == name && record.emitter_node_id().as_ref() == Some(emitter))
+            })
+            .collect()
+    }
+
+    pub fn count_by_name(&self) -> IndexMap<String, u64> {
+        let mut ret: IndexMap<String, u64> = IndexMap::new();
+        for record in &self.records {
+            *ret.entry(record.name.clone()).or_default() += 1;
+        }
+        ret
+    }
+
+    pub fn print_summary(&self) {
+        for (name, count) in self.count_by_name() {
+            println!("-- Event {}: {}", name, count);
+        }
+        println!("Events: {}", self.records.len());
+    }
+
+    pub fn write_csv(&self, path: &Path) {
+        let encoder = AddressBech32Encoder::new(crate::network());
+        let mut csv = provenance::csv_writer(path);
+        csv.write_record(["Day", "Epoch", "Transaction", "Operation", "Emitter", "Event", "Payload"]).unwrap();
+        for record in &self.records {
+            csv.write_record(&[
+                record.day.to_string(),
+                record.epoch.to_string(),
+                record.transaction.to_string(),
+                record.operation.clone(),
+                record.emitter_display(&encoder),
+                record.name.clone(),
+                record.payload.clone(),
+            ]).unwrap();
+        }
+        csv.flush().unwrap();
+    }
+}
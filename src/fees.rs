@@ -0,0 +1,215 @@
+use std::path::Path;
+
+use radix_engine::transaction::*;
+use radix_engine_common::prelude::*;
+use radix_engine_derive::ScryptoSbor;
+use radix_engine_interface::macros::dec;
+
+use crate::provenance;
+
+// operations paid by suppliers and borrowers, all other recovery plan operations are paid by the operator
+pub const USER_OPERATIONS: [&str; 3] = ["withdraw_lsu", "deposit_lsu", "flash_loan"];
+
+#[derive(Clone, Debug, ScryptoSbor)]
+pub struct FeeRecord {
+    pub day: u64,
+    pub epoch: u64,
+    pub operation: String,
+    // transaction only models something happening outside of the recovery plan
+    pub simulated: bool,
+    pub success: bool,
+    pub execution_cost_units: u32,
+    pub finalization_cost_units: u32,
+    pub execution_cost: Decimal,
+    pub finalization_cost: Decimal,
+    pub storage_cost: Decimal,
+    pub royalty_cost: Decimal,
+    pub total_cost: Decimal,
+}
+
+impl FeeRecord {
+    pub fn from_receipt(day: u64, epoch: u64, operation: &str, simulated: bool, receipt: &TransactionReceiptV1) -> Self {
+        let fee_summary = &receipt.fee_summary;
+        FeeRecord {
+            day,
+            epoch,
+            operation: operation.to_string(),
+            simulated,
+            success: receipt.is_commit_success(),
+            execution_cost_units: fee_summary.total_execution_cost_units_consumed,
+            finalization_cost_units: fee_summary.total_finalization_cost_units_consumed,
+            execution_cost: fee_summary.total_execution_cost_in_xrd,
+            finalization_cost: fee_summary.total_finalization_cost_in_xrd,
+            storage_cost: fee_summary.total_storage_cost_in_xrd,
+            royalty_cost: fee_summary.total_royalty_cost_in_xrd,
+            total_cost: fee_summary.total_cost(),
+        }
+    }
+
+    pub fn paid_by_operator(&self) -> bool {
+        !self.simulated && !USER_OPERATIONS.contains(&self.operation.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FeeTotals {
+    pub transactions: u64,
+    pub failed_transactions: u64,
+    pub total_cost: Decimal,
+    // split of `total_cost`, simulated transactions are in neither
+    pub operator_cost: Decimal,
+    pub user_cost: Decimal,
+    pub max_cost: Decimal,
+    pub max_execution_cost_units: u32,
+    pub max_finalization_cost_units: u32,
+}
+
+impl FeeTotals {
+    fn add(&mut self, record: &FeeRecord) {
+        self.transactions += 1;
+        if !record.success {
+            self.failed_transactions += 1;
+        }
+        self.total_cost += record.total_cost;
+        if record.paid_by_operator() {
+            self.operator_cost += record.total_cost;
+        } else if !record.simulated {
+            self.user_cost += record.total_cost;
+        }
+        self.max_cost = self.max_cost.max(record.total_cost);
+        self.max_execution_cost_units = self.max_execution_cost_units.max(record.execution_cost_units);
+        self.max_finalization_cost_units = self.max_finalization_cost_units.max(record.finalization_cost_units);
+    }
+
+    // share of the per transaction execution cost unit limit used by the most expensive transaction
+    pub fn max_execution_limit_usage(&self) -> f64 {
+        self.max_execution_cost_units as f64 / EXECUTION_COST_UNIT_LIMIT as f64
+    }
+}
+
+#[derive(Clone, Debug, Default, ScryptoSbor)]
+pub struct FeeLedger {
+    pub records: Vec<FeeRecord>,
+}
+
+impl FeeLedger {
+    pub fn per_operation(&self) -> IndexMap<String, FeeTotals> {
+        let mut ret: IndexMap<String, FeeTotals> = IndexMap::new();
+        for record in &self.records {
+            ret.entry(record.operation.clone()).or_default().add(record);
+        }
+        ret
+    }
+
+    pub fn per_day(&self) -> IndexMap<u64, FeeTotals> {
+        let mut ret: IndexMap<u64, FeeTotals> = IndexMap::new();
+        for record in &self.records {
+            ret.entry(record.day).or_default().add(record);
+        }
+        ret
+    }
+
+    // indices of records of given operation, which are also the transaction indices of collected events
+    pub fn transactions(&self, operation: &str) -> Vec<usize> {
+        self.records.iter().enumerate().filter(|(_index, record)| record.operation == operation).map(|(index, _record)| index).collect()
+    }
+
+    pub fn transactions_per_day(&self, operation: &str) -> IndexMap<u64, u64> {
+        let mut ret: IndexMap<u64, u64> = IndexMap::new();
+        for record in self.records.iter().filter(|record| record.operation == operation) {
+            *ret.entry(record.day).or_default() += 1;
+        }
+        ret
+    }
+
+    pub fn operator_cost(&self) -> Decimal {
+        self.records
+            .iter()
+            .filter(|record| record.paid_by_operator())
+            .fold(dec!(0), |sum, record| sum + record.total_cost)
+    }
+
+    pub fn user_cost(&self) -> Decimal {
+        self.records
+            .iter()
+            .filter(|record| !record.simulated && !record.paid_by_operator())
+            .fold(dec!(0), |sum, record| sum + record.total_cost)
+    }
+
+    pub fn print_summary(&self) {
+        println!("{:<36} {:>8} {:>8} {:>28} {:>28} {:>14} {:>8}", "Operation", "Txs", "Failed", "Total fee (XRD)", "Max fee (XRD)", "Max exec CU", "Limit");
+        for (operation, totals) in self.per_operation() {
+            println!(
+                "{:<36} {:>8} {:>8} {:>28} {:>28} {:>14} {:>7.2}%",
+                operation,
+                totals.transactions,
+                totals.failed_transactions,
+                totals.total_cost,
+                totals.max_cost,
+                totals.max_execution_cost_units,
+                totals.max_execution_limit_usage() * 100.0
+            );
+        }
+        println!("Fees paid by operator: {} XRD, by users: {} XRD", self.operator_cost(), self.user_cost());
+        println!("{:<6} {:>8} {:>8} {:>28} {:>28} {:>28}", "Day", "Txs", "Failed", "Total fee (XRD)", "Operator fee (XRD)", "User fee (XRD)");
+        for (day, totals) in self.per_day() {
+            println!("{:<6} {:>8} {:>8} {:>28} {:>28} {:>28}", day, totals.transactions, totals.failed_transactions, totals.total_cost, totals.operator_cost, totals.user_cost);
+        }
+        let update_transactions = self.transactions_per_day("update_supplier_kvs");
+        if let Some(max) = update_transactions.values().max() {
+            println!("update_supplier_kvs transactions per day: up to {}, in total {}", max, update_transactions.values().sum::<u64>());
+        }
+    }
+
+    pub fn write_csv(&self, path: &Path) {
+        let mut csv = provenance::csv_writer(path);
+        csv.write_record([
+            "Day",
+            "Epoch",
+            "Operation",
+            "Simulated",
+            "Success",
+            "Execution cost units",
+            "Finalization cost units",
+            "Execution cost",
+            "Finalization cost",
+            "Storage cost",
+            "Royalty cost",
+            "Total cost",
+        ]).unwrap();
+        for record in &self.records {
+            csv.write_record(&[
+                record.day.to_string(),
+                record.epoch.to_string(),
+                record.operation.clone(),
+                record.simulated.to_string(),
+                record.success.to_string(),
+                record.execution_cost_units.to_string(),
+                record.finalization_cost_units.to_string(),
+                record.execution_cost.to_string(),
+                record.finalization_cost.to_string(),
+                record.storage_cost.to_string(),
+                record.royalty_cost.to_string(),
+                record.total_cost.to_string(),
+            ]).unwrap();
+        }
+        csv.flush().unwrap();
+    }
+
+    pub fn write_per_day_csv(&self, path: &Path) {
+        let mut csv = provenance::csv_writer(path);
+        csv.write_record(["Day", "Transactions", "Failed", "Total cost", "Operator cost", "User cost", "Max execution cost units"]).unwrap();
+        for (day, totals) in self.per_day() {
+            csv.write_record(&[
+                day.to_string(),
+                totals.transactions.to_string(),
+                totals.failed_transactions.to_string(),
+                totals.total_cost.to_string(),
+                totals.operator_cost.to_string(),
+                totals.user_cost.to_string(),
+                totals.max_execution_cost_units.to_string(),
+            ]).unwrap();
+        }
+        csv.flush().unwrap();
+    }
+}
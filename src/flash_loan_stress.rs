@@ -0,0 +1,162 @@
+use std::path::Path;
+
+use radix_engine_common::prelude::*;
+use radix_engine_interface::macros::dec;
+
+use crate::gable_simulation::{receipt_error, GableSimulation};
+use crate::provenance;
+
+pub struct FlashLoanCase {
+    pub name: String,
+    // (amount, repayment) of every loan, all loans are taken before the first one is repaid
+    pub loans: Vec<(Decimal, Decimal)>,
+    // owner withdraws all owner liquidity before the loan
+    pub withdraw_owner_liquidity: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Liquidity {
+    pub xrd_balance: Decimal,
+    pub owner_liquidity: Decimal,
+    pub pool_liquidity: Decimal,
+    pub rewards_liquidity: Decimal,
+}
+
+impl Liquidity {
+    pub fn capture(simulation: &mut GableSimulation) -> Self {
+        Liquidity {
+            xrd_balance: simulation.get_xrd_balance(),
+            owner_liquidity: simulation.get_owner_liqudity(),
+            pool_liquidity: simulation.get_pool_liqudity(),
+            rewards_liquidity: simulation.gable_state().rewards_liquidity,
+        }
+    }
+}
+
+pub struct FlashLoanOutcome {
+    pub case: String,
+    pub borrowed: Decimal,
+    pub repaid: Decimal,
+    // engine error, None if the loan transaction succeeded
+    pub error: Option<String>,
+    // after owner liquidity withdrawal if the case withdraws it
+    pub before: Liquidity,
+    pub after: Liquidity,
+}
+
+// Loans of 1%, 10%, 50% and 100% of the vault and more than the vault, repaid with interest,
+// then shortfalls, nested loans and a loan of the whole pool after owner liquidity is withdrawn.
+pub fn default_cases(simulation: &mut GableSimulation) -> Vec<FlashLoanCase> {
+    let vault = simulation.get_xrd_balance();
+    let pool = simulation.get_pool_liqudity();
+    let mut with_interest = |amount: Decimal| (amount, amount + simulation.get_flash_loan_interest(amount));
+
+    let mut cases = Vec::new();
+    for (name, amount) in [
+        ("1% of vault", vault * dec!("0.01")),
+        ("10% of vault", vault * dec!("0.1")),
+        ("50% of vault", vault * dec!("0.5")),
+        ("full vault", vault),
+        ("vault + 1 XRD", vault + dec!(1)),
+    ] {
+        cases.push(FlashLoanCase { name: name.to_string(), loans: vec![with_interest(amount)], withdraw_owner_liquidity: false });
+    }
+
+    let amount = vault * dec!("0.5");
+    let (_, repayment) = with_interest(amount);
+    cases.push(FlashLoanCase { name: "principal without interest".to_string(), loans: vec![(amount, amount)], withdraw_owner_liquidity: false });
+    cases.push(FlashLoanCase { name: "interest short by 1 attoXRD".to_string(), loans: vec![(amount, repayment - dec!("0.000000000000000001"))], withdraw_owner_liquidity: false });
+    cases.push(FlashLoanCase { name: "half of principal".to_string(), loans: vec![(amount, amount * dec!("0.5"))], withdraw_owner_liquidity: false });
+
+    cases.push(FlashLoanCase { name: "two nested loans of 25% of vault".to_string(), loans: vec![with_interest(vault * dec!("0.25")), with_interest(vault * dec!("0.25"))], withdraw_owner_liquidity: false });
+    cases.push(FlashLoanCase { name: "two nested loans of 10% and 90% of vault".to_string(), loans: vec![with_interest(vault * dec!("0.1")), with_interest(vault * dec!("0.9"))], withdraw_owner_liquidity: false });
+    cases.push(FlashLoanCase { name: "two nested loans of full vault".to_string(), loans: vec![with_interest(vault), with_interest(vault)], withdraw_owner_liquidity: false });
+
+    cases.push(FlashLoanCase { name: "pool liquidity without owner liquidity".to_string(), loans: vec![with_interest(pool)], withdraw_owner_liquidity: true });
+    cases.push(FlashLoanCase { name: "snapshot vault without owner liquidity".to_string(), loans: vec![with_interest(vault)], withdraw_owner_liquidity: true });
+    cases
+}
+
+// Runs every case from the same state, the simulation is restored after each case.
+pub fn run(simulation: &mut GableSimulation, cases: &[FlashLoanCase]) -> Vec<FlashLoanOutcome> {
+    let snapshot = simulation.snapshot();
+    let mut outcomes = Vec::new();
+    for case in cases {
+        if case.withdraw_owner_liquidity {
+            let owner_liquidity = simulation.get_owner_liqudity();
+            if owner_liquidity > dec!(0) {
+                simulation.owner_withdraw_xrd(owner_liquidity);
+            }
+        }
+        let before = Liquidity::capture(simulation);
+        let receipt = simulation.flash_loans(simulation.account, &case.loans);
+        let after = Liquidity::capture(simulation);
+        outcomes.push(FlashLoanOutcome {
+            case: case.name.clone(),
+            borrowed: case.loans.iter().fold(dec!(0), |sum, (amount, _repayment)| sum + *amount),
+            repaid: case.loans.iter().fold(dec!(0), |sum, (_amount, repayment)| sum + *repayment),
+            error: receipt_error(&receipt),
+            before,
+            after,
+        });
+        simulation.restore(&snapshot);
+    }
+    outcomes
+}
+
+pub fn print_outcomes(outcomes: &[FlashLoanOutcome]) {
+    for outcome in outcomes {
+        match &outcome.error {
+            None => println!("-- {}: borrowed {}, repaid {}, succeeded", outcome.case, outcome.borrowed, outcome.repaid),
+            Some(error) => println!("-- {}: borrowed {}, repaid {}, failed: {}", outcome.case, outcome.borrowed, outcome.repaid, error),
+        }
+        println!(
+            "---- Pool liquidity: {} → {}, owner liquidity: {} → {}, rewards liquidity: {} → {}",
+            outcome.before.pool_liquidity,
+            outcome.after.pool_liquidity,
+            outcome.before.owner_liquidity,
+            outcome.after.owner_liquidity,
+            outcome.before.rewards_liquidity,
+            outcome.after.rewards_liquidity,
+        );
+    }
+    let failed = outcomes.iter().filter(|outcome| outcome.error.is_some()).count();
+    println!("Flash loan cases: {}, succeeded: {}, failed: {}", outcomes.len(), outcomes.len() - failed, failed);
+}
+
+pub fn write_csv(outcomes: &[FlashLoanOutcome], path: &Path) {
+    let mut csv = provenance::csv_writer(path);
+    csv.write_record([
+        "Case",
+        "Borrowed",
+        "Repaid",
+        "Success",
+        "Error",
+        "XRD balance before",
+        "XRD balance after",
+        "Pool liquidity before",
+        "Pool liquidity after",
+        "Owner liquidity before",
+        "Owner liquidity after",
+        "Rewards liquidity before",
+        "Rewards liquidity after",
+    ]).unwrap();
+    for outcome in outcomes {
+        csv.write_record(&[
+            outcome.case.clone(),
+            outcome.borrowed.to_string(),
+            outcome.repaid.to_string(),
+            outcome.error.is_none().to_string(),
+            outcome.error.clone().unwrap_or_default(),
+            outcome.before.xrd_balance.to_string(),
+            outcome.after.xrd_balance.to_string(),
+            outcome.before.pool_liquidity.to_string(),
+            outcome.after.pool_liquidity.to_string(),
+            outcome.before.owner_liquidity.to_string(),
+            outcome.after.owner_liquidity.to_string(),
+            outcome.before.rewards_liquidity.to_string(),
+            outcome.after.rewards_liquidity.to_string(),
+        ]).unwrap();
+    }
+    csv.flush().unwrap();
+}
@@ -1,4 +1,6 @@
 
+use std::collections::VecDeque;
+
 use radix_engine::blueprints::models::FieldPayload;
 use radix_engine::blueprints::models::IndexEntryPayload;
 use radix_engine::system::system_db_reader::SystemDatabaseWriter;
@@ -12,7 +14,18 @@ use radix_engine_common::prelude::*;
 use substate_store_impls::rocks_db::RocksdbSubstateStore;
 use substate_store_interface::db_key_mapper::MappedCommittableSubstateDatabase;
 use substate_store_interface::db_key_mapper::SpreadPrefixKeyMapper;
+use substate_store_interface::interface::CommittableSubstateDatabase;
+use substate_store_interface::interface::DatabaseUpdates;
 use substate_store_impls::substate_database_overlay::*;
+use substate_store_queries::typed_substate_layout::ConsensusManagerConfigurationFieldPayload;
+use substate_store_queries::typed_substate_layout::ConsensusManagerField;
+use substate_store_queries::typed_substate_layout::ConsensusManagerProposerMilliTimestampFieldPayload;
+use substate_store_queries::typed_substate_layout::ConsensusManagerStateFieldPayload;
+use substate_store_queries::typed_substate_layout::FungibleResourceManagerField;
+use substate_store_queries::typed_substate_layout::FungibleResourceManagerTotalSupplyFieldPayload;
+use substate_store_queries::typed_substate_layout::FungibleVaultBalanceFieldPayload;
+use substate_store_queries::typed_substate_layout::FungibleVaultField;
+use substate_store_queries::typed_substate_layout::LiquidFungibleResource;
 use substate_store_queries::typed_substate_layout::NonFungibleVaultBalanceFieldPayload;
 use substate_store_queries::typed_substate_layout::NonFungibleVaultCollection;
 use substate_store_queries::typed_substate_layout::NonFungibleVaultField;
@@ -22,55 +35,210 @@ use substate_store_queries::typed_substate_layout::UnstakeData;
 use substate_store_queries::typed_substate_layout::ValidatorStateFieldPayload;
 use substate_store_queries::typed_substate_layout::ValidatorStateV1;
 use transaction::builder::ManifestBuilder;
+use transaction::builder::ResolvableArguments;
 use transaction::model::TestTransaction;
+use transaction::model::RawNotarizedTransaction;
 use transaction::model::TransactionManifestV1;
+use transaction::validation::NotarizedTransactionValidator;
+use transaction::validation::TransactionValidator;
+use transaction::validation::ValidationConfig;
 use radix_engine_interface::types::CollectionDescriptor;
 use radix_engine::system::system_db_reader::SystemDatabaseReader;
 use radix_engine::system::system_modules::*;
 use radix_engine::transaction::*;
 use radix_engine::vm::*;
 use radix_engine_interface::blueprints::account::*;
+use radix_engine_interface::blueprints::package::MethodAuthTemplate;
+use radix_engine_interface::blueprints::resource::{MethodAccessibility, OWNER_ROLE};
+use radix_engine_interface::blueprints::consensus_manager::ConsensusManagerConfig;
 use extend::*;
 
+use crate::batching::{is_limit_error, SupplierKvsBatching};
+use crate::events::EventLog;
+use crate::fees::{FeeLedger, FeeRecord};
+use crate::pools::PoolConfig;
+use crate::scheduler::Scheduler;
 use crate::structures::Flashloanpool;
+use crate::unlock_pipeline::{PipelineState, UnlockPipeline};
 
 pub type GableSimulationTestRunner<'a> = LedgerSimulator<NoExtension, SubstateDatabaseOverlay<&'a RocksdbSubstateStore, RocksdbSubstateStore>>;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthMode {
+    Disabled,
+    // signer proofs of simulated accounts are added to every transaction
+    Enabled,
+}
+
 pub struct GableSimulation<'a> {
     pub test_runner: GableSimulationTestRunner<'a>,
     pub gable_component: ComponentAddress,
     pub gable_validator: ComponentAddress,
     pub gable_owner_account: ComponentAddress,
     pub gable_owner_badge: ResourceAddress,
+    pub admin_badge: ResourceAddress,
     pub validator_owner_badge: NonFungibleLocalId,
     pub lsu: ResourceAddress,
     pub pool_nft: ResourceAddress,
+    pub transient_token: ResourceAddress,
     pub account: ComponentAddress,
+    pub auth_mode: AuthMode,
+    // current day of a scenario, used to attribute fees and events
+    pub day: u64,
+    pub fees: FeeLedger,
+    pub supplier_kvs_batching: SupplierKvsBatching,
+    pub events: EventLog,
+    pub unlock_pipeline: UnlockPipeline,
+    // timed events of the event-driven scenario timing
+    pub scheduler: Scheduler,
+    // receipts of executed transactions are collected only if enabled
+    pub keep_receipts: bool,
+    pub receipts: Vec<TransactionReceiptV1>,
+}
+
+// state of a simulation at a point in time, see `GableSimulation::snapshot`
+#[derive(Clone)]
+pub struct SimulationSnapshot {
+    pub database_updates: DatabaseUpdates,
+    pub fees: FeeLedger,
+    pub supplier_kvs_batching: SupplierKvsBatching,
+    pub events: EventLog,
+    pub unlock_pipeline: UnlockPipeline,
+    pub scheduler: Scheduler,
 }
 
 impl<'a> GableSimulation<'a> {
-    pub fn new(mut test_runner: GableSimulationTestRunner<'a>) -> Self {        
-        let decoder = AddressBech32Decoder::new(&NetworkDefinition::mainnet());
-        let gable_component = ComponentAddress::try_from_bech32(&decoder, "component_rdx1cpmh7lyg0hx6efv5q79lv6rqxdqpuh27y99nzm0jpwu2u44ne243ws").unwrap();
-        let gable_validator = ComponentAddress::try_from_bech32(&decoder, "validator_rdx1sdf04wxuc7c4llwst8rw5sfj350gnlnluhrpy09wk2gwk5cmvgffpy").unwrap();
-        let gable_owner_account = ComponentAddress::try_from_bech32(&decoder, "account_rdx12y0frj9mjxmlc36gggsts826jsp2e6wk40tv85prpve32kx2u360y3").unwrap();
-        let gable_owner_badge: ResourceAddress = ResourceAddress::try_from_bech32(&decoder, "resource_rdx1t4zd2h95htm79dmyr9d422qy4c03urvkutqxgsyxx9udcmrdgk9s22").unwrap();
-        let validator_owner_badge: NonFungibleLocalId = NonFungibleLocalId::from_str("[8352fab8dcc7b15ffdd059c6ea41328d1e89fe7fe5c6123caeb290eb531b]").unwrap();
-        let lsu: ResourceAddress = ResourceAddress::try_from_bech32(&decoder, "resource_rdx1thrz4g8g83802lumrtrdsrhjd6k5uxhxhgkrwjg0jn75cvxfc99nap").unwrap();
+    pub fn new(mut test_runner: GableSimulationTestRunner<'a>, pool: &PoolConfig) -> Self {
+        let account = test_runner.new_account_with_xrd();
+        Self::with_account(test_runner, pool, account)
+    }
+
+    // account has to exist in the database of the test runner, used when resuming from a checkpoint
+    pub fn with_account(mut test_runner: GableSimulationTestRunner<'a>, pool: &PoolConfig, account: ComponentAddress) -> Self {
+        let decoder = AddressBech32Decoder::new(crate::network());
+        let addresses = pool.decode(&decoder).unwrap_or_else(|error| panic!("{}", error));
+        let gable_component = addresses.component;
+        let gable_validator = addresses.validator;
+        let gable_owner_account = addresses.owner_account;
         let gable_state : Flashloanpool = test_runner.component_state(gable_component);
+        let validator_state: ValidatorStateV1 = test_runner.component_state::<ValidatorStateFieldPayload>(gable_validator).into_latest();
+        let gable_owner_badge = addresses.owner_badge.unwrap_or(gable_state.owner_badge_address);
+        let lsu = addresses.lsu.unwrap_or(validator_state.stake_unit_resource);
+        // the validator owner badge is identified by the bytes of the validator address
+        let validator_owner_badge = addresses
+            .validator_owner_badge
+            .unwrap_or_else(|| NonFungibleLocalId::bytes(gable_validator.as_node_id().0.to_vec()).unwrap());
         let pool_nft = gable_state.pool_nft.address();
-        let account = test_runner.new_account_with_xrd();
-        GableSimulation {
+        let transient_token = gable_state.transient_token.address();
+        let admin_badge = gable_state.admin_badge_address;
+        let mut simulation = GableSimulation {
             test_runner,
             gable_component,
             gable_validator,
             gable_owner_account,
             gable_owner_badge,
+            admin_badge,
             validator_owner_badge,
             lsu,
             pool_nft,
-            account
+            transient_token,
+            account,
+            auth_mode: AuthMode::Disabled,
+            day: 0,
+            fees: FeeLedger::default(),
+            supplier_kvs_batching: SupplierKvsBatching::default(),
+            events: EventLog::default(),
+            unlock_pipeline: UnlockPipeline::default(),
+            scheduler: Scheduler::default(),
+            keep_receipts: false,
+            receipts: Vec::new(),
+        };
+        let state = simulation.unlock_pipeline_state();
+        simulation.unlock_pipeline.seed(&state);
+        simulation
+    }
+
+    // executes operation of the recovery plan, with auth checks if enabled
+    pub fn execute_manifest(&mut self, operation: &str, manifest: TransactionManifestV1) -> TransactionReceiptV1 {
+        let receipt = self.execute_unrecorded_manifest(manifest);
+        self.record_receipt(operation, false, receipt)
+    }
+
+    // like `execute_manifest`, but the caller decides how the receipt is recorded
+    fn execute_unrecorded_manifest(&mut self, manifest: TransactionManifestV1) -> TransactionReceiptV1 {
+        match self.auth_mode {
+            AuthMode::Disabled => self.test_runner.execute_manifest_without_auth(manifest),
+            AuthMode::Enabled => {
+                let proofs = self.signer_proofs();
+                self.test_runner.execute_manifest_with_auth(manifest, proofs)
+            }
+        }
+    }
+
+    // executes manifest which only models something happening outside of the recovery plan
+    // (validator rewards, nft duplicates, funding), auth is always disabled
+    pub fn execute_simulation_manifest(&mut self, operation: &str, manifest: TransactionManifestV1) -> TransactionReceiptV1 {
+        let receipt = self.test_runner.execute_manifest_without_auth(manifest);
+        self.record_receipt(operation, true, receipt)
+    }
+
+    pub fn execute_notarized_transaction(&mut self, operation: &str, raw_transaction: &RawNotarizedTransaction) -> Result<TransactionReceiptV1, String> {
+        let receipt = self.test_runner.execute_raw_notarized_transaction(raw_transaction)?;
+        Ok(self.record_receipt(operation, false, receipt))
+    }
+
+    pub fn execute_manifest_with_auth(&mut self, operation: &str, manifest: TransactionManifestV1, initial_proofs: BTreeSet<NonFungibleGlobalId>) -> TransactionReceiptV1 {
+        let receipt = self.test_runner.execute_manifest_with_auth(manifest, initial_proofs);
+        self.record_receipt(operation, false, receipt)
+    }
+
+    fn record_receipt(&mut self, operation: &str, simulated: bool, receipt: TransactionReceiptV1) -> TransactionReceiptV1 {
+        let epoch = self.test_runner.get_current_epoch().number();
+        let transaction = self.fees.records.len();
+        if self.events.enabled {
+            self.events.collect(&mut self.test_runner, self.day, epoch, transaction, operation, &receipt);
         }
+        self.fees.records.push(FeeRecord::from_receipt(self.day, epoch, operation, simulated, &receipt));
+        if self.keep_receipts {
+            self.receipts.push(receipt.clone());
+        }
+        receipt
+    }
+
+    // virtual signature proofs of the simulation account and Gable owner account
+    pub fn signer_proofs(&self) -> BTreeSet<NonFungibleGlobalId> {
+        [self.account, self.gable_owner_account]
+            .iter()
+            .map(|account| virtual_signature_badge(*account).expect("Only virtual accounts can sign in simulation"))
+            .collect()
+    }
+
+    // changes made on top of the snapshot database with the in-memory trackers, so a restore doesn't keep records of undone steps
+    pub fn snapshot(&self) -> SimulationSnapshot {
+        SimulationSnapshot {
+            database_updates: self.test_runner.substate_db().database_updates(),
+            fees: self.fees.clone(),
+            supplier_kvs_batching: self.supplier_kvs_batching.clone(),
+            events: self.events.clone(),
+            unlock_pipeline: self.unlock_pipeline.clone(),
+            scheduler: self.scheduler.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: &SimulationSnapshot) {
+        let mut test_runner = crate::new_test_runner();
+        test_runner.substate_db_mut().commit(&snapshot.database_updates);
+        self.test_runner = test_runner;
+        self.restore_trackers(snapshot);
+    }
+
+    // in-memory trackers of the snapshot, the ledger is kept as it is
+    pub(crate) fn restore_trackers(&mut self, snapshot: &SimulationSnapshot) {
+        self.fees = snapshot.fees.clone();
+        self.supplier_kvs_batching = snapshot.supplier_kvs_batching.clone();
+        self.events = snapshot.events.clone();
+        self.unlock_pipeline = snapshot.unlock_pipeline.clone();
+        self.scheduler = snapshot.scheduler.clone();
     }
 
     pub fn gable_state(&mut self) -> Flashloanpool {
@@ -116,8 +284,34 @@ impl<'a> GableSimulation<'a> {
         nfts
     }
 
+    // nfts skipped by `get_lsu_claims(true)`, one (with lowest LSU) per group
+    pub fn get_last_nft_in_each_group(&mut self) -> IndexMap<u64, (NonFungibleLocalId, (Decimal, Decimal))> {
+        let nft_groups = self.get_supplier_partitioned_kvs();
+        let mut ret: IndexMap<u64, (NonFungibleLocalId, (Decimal, Decimal))> = IndexMap::new();
+        for (group, mut group_nfts) in nft_groups {
+            group_nfts.sort_by(|_k1, v1, _k2, v2| v2[0].cmp(&v1[0]));
+            if let Some((nft, amounts)) = group_nfts.pop() {
+                ret.insert(group, (nft, (amounts[0], amounts[1] + amounts[2])));
+            }
+        }
+        ret
+    }
+
+    pub fn get_lsu_stranded_by_group_workaround(&mut self) -> Decimal {
+        self.get_last_nft_in_each_group()
+            .values()
+            .fold(dec!(0), |sum, (_nft, (lsu, _xrd))| sum + *lsu)
+    }
+
+    // duplicate of a supplier nft in the account, unless the account already holds it (e.g. minted by `deposit_lsu`)
+    pub fn acquire_nft(&mut self, account: ComponentAddress, nft: NonFungibleLocalId) {
+        if !self.get_pool_nfts(account).contains(&nft) {
+            self.create_nft_duplicate(account, nft);
+        }
+    }
+
     pub fn create_nft_duplicate(&mut self, account: ComponentAddress, nft: NonFungibleLocalId) {
-        self.test_runner.execute_manifest_without_auth(ManifestBuilder::new()
+        self.execute_simulation_manifest("create_nft_duplicate", ManifestBuilder::new()
             .lock_fee(self.account, dec!(10))
             .take_from_worktop(self.pool_nft, 0, "bucket")
             .with_bucket("bucket", |builder, bucket| {
@@ -188,17 +382,104 @@ impl<'a> GableSimulation<'a> {
         validator_state.pending_owner_stake_unit_withdrawals
     }
 
-    pub fn get_pending_unstakes(&mut self) -> Vec<(Epoch, Decimal)> {        
+    pub fn get_pending_unstakes(&mut self) -> Vec<(Epoch, Decimal)> {
+        self.get_unstake_claims().into_values().collect()
+    }
+
+    // claim epoch and XRD amount of unstake claim nfts in `unstaking_nft_vault`
+    pub fn get_unstake_claims(&mut self) -> IndexMap<NonFungibleLocalId, (Epoch, Decimal)> {
         let gable_state = self.gable_state();
         let validator_state: ValidatorStateV1 = self.validator_state();
-        let mut ret = Vec::new();
+        let mut ret = IndexMap::new();
         for nft in &gable_state.nft_vec {
             let nft_data: UnstakeData = self.test_runner.get_non_fungible_data(validator_state.claim_nft, nft.clone());
-            ret.push((nft_data.claim_epoch, nft_data.claim_amount));
+            ret.insert(nft.clone(), (nft_data.claim_epoch, nft_data.claim_amount));
         }
         ret
     }
 
+    pub fn unlock_pipeline_state(&mut self) -> PipelineState {
+        PipelineState {
+            epoch: self.test_runner.get_current_epoch().number(),
+            owner_unlocks: self.get_pending_owner_unlocks().into_iter().map(|(epoch, lsu)| (epoch.number(), lsu)).collect(),
+            unstake_claims: self.get_unstake_claims().into_iter().map(|(nft, (epoch, xrd))| (nft, (epoch.number(), xrd))).collect(),
+            liquidity_pool_xrd: self.test_runner.inspect_vault_balance(*self.gable_state().liquidity_pool_vault.0.as_node_id()).unwrap_or_default(),
+        }
+    }
+
+    // network configuration, e.g. `num_owner_stake_units_unlock_epochs` and `num_unstake_epochs`
+    pub fn consensus_manager_config(&mut self) -> ConsensusManagerConfig {
+        let reader = SystemDatabaseReader::new(self.test_runner.substate_db());
+        let config: ConsensusManagerConfigurationFieldPayload = reader
+            .read_typed_object_field(
+                CONSENSUS_MANAGER.as_node_id(),
+                ModuleId::Main,
+                ConsensusManagerField::Configuration.into(),
+            )
+            .unwrap();
+        config.into_latest().config
+    }
+
+    // epoch and round of the consensus manager
+    pub fn consensus_manager_round(&mut self) -> (u64, u64) {
+        let reader = SystemDatabaseReader::new(self.test_runner.substate_db());
+        let state: ConsensusManagerStateFieldPayload = reader
+            .read_typed_object_field(
+                CONSENSUS_MANAGER.as_node_id(),
+                ModuleId::Main,
+                ConsensusManagerField::State.into(),
+            )
+            .unwrap();
+        let state = state.into_latest();
+        (state.epoch.number(), state.round.number())
+    }
+
+    // timestamp of the last proposal in milliseconds since unix epoch
+    pub fn proposer_milli_timestamp(&mut self) -> i64 {
+        let reader = SystemDatabaseReader::new(self.test_runner.substate_db());
+        let timestamp: ConsensusManagerProposerMilliTimestampFieldPayload = reader
+            .read_typed_object_field(
+                CONSENSUS_MANAGER.as_node_id(),
+                ModuleId::Main,
+                ConsensusManagerField::ProposerMilliTimestamp.into(),
+            )
+            .unwrap();
+        timestamp.into_latest().epoch_milli
+    }
+
+    // XRD staked to the Gable validator
+    pub fn get_validator_stake(&mut self) -> Decimal {
+        let stake_xrd_vault_id = self.validator_state().stake_xrd_vault_id;
+        self.test_runner.inspect_vault_balance(stake_xrd_vault_id.0).unwrap_or_default()
+    }
+
+    pub fn get_lsu_total_supply(&mut self) -> Decimal {
+        let reader = SystemDatabaseReader::new(self.test_runner.substate_db());
+        let total_supply: FungibleResourceManagerTotalSupplyFieldPayload = reader
+            .read_typed_object_field(
+                self.lsu.as_node_id(),
+                ModuleId::Main,
+                FungibleResourceManagerField::TotalSupply.into(),
+            )
+            .unwrap();
+        total_supply.into_latest()
+    }
+
+    // XRD redeemable for one LSU of the Gable validator
+    pub fn get_lsu_redemption_rate(&mut self) -> Decimal {
+        let total_supply = self.get_lsu_total_supply();
+        if total_supply.is_zero() {
+            return dec!(1);
+        }
+        self.get_validator_stake() / total_supply
+    }
+
+    // transactions of `withdraw_lsu` without a withdraw event from the Gable LSU vault, events have to be collected
+    pub fn withdraw_lsu_transactions_without_vault_withdraw(&mut self) -> Vec<usize> {
+        let lsu_vault = *self.gable_state().lsu_vault.0.as_node_id();
+        self.events.transactions_missing_event(self.fees.transactions("withdraw_lsu"), &lsu_vault, "WithdrawEvent")
+    }
+
     pub fn get_owner_xrd_balance(&mut self) -> Decimal {
         self.test_runner.get_component_balance(self.gable_owner_account, XRD)
     }
@@ -224,27 +505,24 @@ impl<'a> GableSimulation<'a> {
     }
 
     pub fn finish_unlock_and_unstake(&mut self) {
-        self.test_runner.execute_manifest_without_auth(ManifestBuilder::new()
+        let before = self.unlock_pipeline_state();
+        self.execute_manifest("finish_unlock_and_unstake", ManifestBuilder::new()
             .lock_fee(self.account, dec!(10))
             .create_proof_from_account_of_amount(self.gable_owner_account, self.gable_owner_badge, dec!(1))
             .call_method(self.gable_component, "finish_unlock_owner_stake_units", (self.gable_validator, self.validator_owner_badge.clone()))
             .call_method(self.gable_component, "unstake", (self.gable_validator,))
             .build()
         ).expect_commit_success();
+        let after = self.unlock_pipeline_state();
+        self.unlock_pipeline.record_finish(self.day, &before, &after);
     }
 
     pub fn owner_withdraw_xrd(&mut self, amount: Decimal) {
-        self.test_runner.execute_manifest_without_auth(ManifestBuilder::new()
-            .lock_fee(self.account, dec!(10))
-            .create_proof_from_account_of_amount(self.gable_owner_account, self.gable_owner_badge, dec!(1))
-            .call_method(self.gable_component, "owner_withdraw_xrd", (amount, ))
-            .try_deposit_entire_worktop_or_abort(self.gable_owner_account, None)
-            .build()
-        ).expect_commit_success();
+        self.call_with_owner_badge("owner_withdraw_xrd", (amount, )).expect_commit_success();
     }
 
     pub fn owner_deposit_xrd(&mut self, amount: Decimal) {
-        self.test_runner.execute_manifest_without_auth(ManifestBuilder::new()
+        self.execute_manifest("owner_deposit_xrd", ManifestBuilder::new()
             .lock_fee(self.account, dec!(10))
             .create_proof_from_account_of_amount(self.gable_owner_account, self.gable_owner_badge, dec!(1))
             .withdraw_from_account(self.gable_owner_account, XRD, amount)
@@ -260,8 +538,194 @@ impl<'a> GableSimulation<'a> {
         ).expect_commit_success();
     }
 
+    // calls a Gable method with a proof of the owner badge of the Gable owner account,
+    // returned resources are deposited to the Gable owner account
+    pub fn call_with_owner_badge(&mut self, method: &str, arguments: impl ResolvableArguments) -> TransactionReceiptV1 {
+        self.execute_manifest(method, ManifestBuilder::new()
+            .lock_fee(self.account, dec!(10))
+            .create_proof_from_account_of_amount(self.gable_owner_account, self.gable_owner_badge, dec!(1))
+            .call_method(self.gable_component, method, arguments)
+            .try_deposit_entire_worktop_or_abort(self.gable_owner_account, None)
+            .build()
+        )
+    }
+
+    // calls a Gable method with a proof of the admin badge, the badge is created in the simulation account
+    // if it doesn't hold one, returned resources are deposited to the simulation account
+    pub fn call_with_admin_badge(&mut self, method: &str, arguments: impl ResolvableArguments) -> Result<TransactionReceiptV1, String> {
+        if self.test_runner.get_component_balance(self.account, self.admin_badge) < dec!(1) {
+            self.create_admin_badge(self.account)?;
+        }
+        Ok(self.execute_manifest(method, ManifestBuilder::new()
+            .lock_fee(self.account, dec!(10))
+            .create_proof_from_account_of_amount(self.account, self.admin_badge, dec!(1))
+            .call_method(self.gable_component, method, arguments)
+            .try_deposit_entire_worktop_or_abort(self.account, None)
+            .build()
+        ))
+    }
+
+    // Gable methods restricted to roles, with the roles, read from the blueprint definition of the Gable package on the ledger
+    pub fn gable_protected_methods(&mut self) -> Result<IndexMap<String, Vec<String>>, String> {
+        let reader = SystemDatabaseReader::new(self.test_runner.substate_db());
+        let object_info = reader.get_object_info(self.gable_component).map_err(|error| format!("Gable component: {:?}", error))?;
+        let definition = reader
+            .get_blueprint_definition(&object_info.blueprint_info.blueprint_id)
+            .map_err(|error| format!("Gable blueprint: {:?}", error))?;
+        let MethodAuthTemplate::StaticRoleDefinition(role_definition) = &definition.auth_config.method_auth else {
+            return Ok(IndexMap::new());
+        };
+        Ok(role_definition
+            .methods
+            .iter()
+            .filter_map(|(method, accessibility)| match accessibility {
+                MethodAccessibility::RoleProtected(roles) => Some((method.ident.clone(), roles.list.iter().map(|role| role.key.clone()).collect())),
+                _ => None,
+            })
+            .collect())
+    }
+
+    // protected Gable methods with a typed wrapper, `inspect methods` lists blueprint methods without one
+    pub const PROTECTED_METHOD_WRAPPERS: [&'static str; 8] = [
+        "owner_deposit_xrd",
+        "owner_withdraw_xrd",
+        "start_unlock_owner_stake_units",
+        "finish_unlock_owner_stake_units",
+        "unstake",
+        "claim_xrd",
+        "update_interest_rate",
+        "update_box_size",
+    ];
+
+    // calls a role protected Gable method, the owner badge is presented for methods open to the owner role,
+    // the admin badge for methods of roles defined by the blueprint, fails if the blueprint has no such method
+    pub fn call_protected(&mut self, method: &str, arguments: impl ResolvableArguments) -> Result<TransactionReceiptV1, String> {
+        let methods = self.gable_protected_methods()?;
+        let roles = methods.get(method).ok_or_else(|| {
+            format!("`{}` is not a role protected method of the Gable blueprint, protected methods: {}", method, methods.keys().cloned().collect::<Vec<_>>().join(", "))
+        })?;
+        if roles.iter().any(|role| role == OWNER_ROLE) {
+            Ok(self.call_with_owner_badge(method, arguments))
+        } else {
+            self.call_with_admin_badge(method, arguments)
+        }
+    }
+
+    // the admin badge holder is not part of the simulation and the badge has a fixed supply,
+    // so the badge is written directly into a vault of the account, total supply is not changed
+    pub fn create_admin_badge(&mut self, account: ComponentAddress) -> Result<(), String> {
+        // a non-fungible badge would need an existing local id with its data, so it has to be held by the account already
+        if !self.admin_badge.is_fungible() {
+            return Err(format!("The admin badge {:?} is non-fungible and the account doesn't hold one", self.admin_badge));
+        }
+        let vault = self.get_or_create_vault(account, self.admin_badge);
+
+        let db = self.test_runner.substate_db_mut();
+        let reader = SystemDatabaseReader::new(db);
+        let vault_balance: FungibleVaultBalanceFieldPayload = reader
+            .read_typed_object_field(
+                &vault,
+                ModuleId::Main,
+                FungibleVaultField::Balance.into(),
+            )
+            .unwrap();
+
+        let mut vault_balance = vault_balance.into_latest();
+        vault_balance.put(LiquidFungibleResource::new(dec!(1)));
+
+        let mut writer = SystemDatabaseWriter::new(db);
+        writer
+            .write_typed_object_field(
+                &vault,
+                ModuleId::Main,
+                FungibleVaultField::Balance.into(),
+                FungibleVaultBalanceFieldPayload::from_content_source(vault_balance),
+            )
+            .unwrap();
+        Ok(())
+    }
+
+    // creates the vault by depositing an empty bucket if the account has none for the resource
+    fn get_or_create_vault(&mut self, account: ComponentAddress, resource: ResourceAddress) -> NodeId {
+        if self.test_runner.get_component_vaults(account, resource).is_empty() {
+            self.execute_simulation_manifest("create_vault", ManifestBuilder::new()
+                .lock_fee(self.account, dec!(10))
+                .take_from_worktop(resource, 0, "bucket")
+                .with_bucket("bucket", |builder, bucket| {
+                    builder.call_method(
+                        account,
+                        "deposit",
+                        (bucket,),
+                    )
+                })
+                .build()
+            ).expect_commit_success();
+        }
+        self.test_runner.get_component_vaults(account, resource)[0]
+    }
+
+    // there is no partial withdraw entry point in the blueprint, so a slice of a claim is paid by moving XRD and LSU
+    // from the pool vaults to the account directly, supplier bookkeeping of the component is not changed
+    pub fn transfer_partial_withdrawal(&mut self, account: ComponentAddress, xrd: Decimal, lsu: Decimal) {
+        let gable_state = self.gable_state();
+        self.move_vault_balance(*gable_state.liquidity_pool_vault.0.as_node_id(), account, XRD, xrd);
+        self.move_vault_balance(*gable_state.lsu_vault.0.as_node_id(), account, self.lsu, lsu);
+    }
+
+    fn move_vault_balance(&mut self, from_vault: NodeId, account: ComponentAddress, resource: ResourceAddress, amount: Decimal) {
+        if amount <= dec!(0) {
+            return;
+        }
+        let to_vault = self.get_or_create_vault(account, resource);
+
+        let db = self.test_runner.substate_db_mut();
+        let reader = SystemDatabaseReader::new(db);
+        let read_balance = |vault: &NodeId| -> LiquidFungibleResource {
+            let balance: FungibleVaultBalanceFieldPayload = reader
+                .read_typed_object_field(
+                    vault,
+                    ModuleId::Main,
+                    FungibleVaultField::Balance.into(),
+                )
+                .unwrap();
+            balance.into_latest()
+        };
+        let mut from_balance = read_balance(&from_vault);
+        let mut to_balance = read_balance(&to_vault);
+        to_balance.put(from_balance.take_by_amount(amount).unwrap());
+
+        let mut writer = SystemDatabaseWriter::new(db);
+        for (vault, balance) in [(from_vault, from_balance), (to_vault, to_balance)] {
+            writer
+                .write_typed_object_field(
+                    &vault,
+                    ModuleId::Main,
+                    FungibleVaultField::Balance.into(),
+                    FungibleVaultBalanceFieldPayload::from_content_source(balance),
+                )
+                .unwrap();
+        }
+    }
+
+    pub fn update_interest_rate(&mut self, interest_rate: Decimal) -> Result<(), String> {
+        self.call_protected_committed("update_interest_rate", (interest_rate,))
+    }
+
+    pub fn update_box_size(&mut self, box_size: u64) -> Result<(), String> {
+        self.call_protected_committed("update_box_size", (box_size,))
+    }
+
+    // like `call_protected`, a failed transaction is returned as an error
+    fn call_protected_committed(&mut self, method: &str, arguments: impl ResolvableArguments) -> Result<(), String> {
+        let receipt = self.call_protected(method, arguments)?;
+        match receipt_error(&receipt) {
+            Some(error) => Err(format!("{} failed: {}", method, error)),
+            None => Ok(()),
+        }
+    }
+
     pub fn unstake(&mut self) {
-        self.test_runner.execute_manifest_without_auth(ManifestBuilder::new()
+        self.execute_manifest("unstake", ManifestBuilder::new()
             .lock_fee(self.account, dec!(10))
             .create_proof_from_account_of_amount(self.gable_owner_account, self.gable_owner_badge, dec!(1))
             .call_method(self.gable_component, "claim_xrd", (self.gable_validator,))
@@ -269,30 +733,69 @@ impl<'a> GableSimulation<'a> {
         ).expect_commit_success();
     }
 
-    pub fn update_supplier_kvs(&mut self) {
+    // recalculates user rewards of all groups, split into as many transactions as needed to stay under limits
+    // returns number of submitted transactions
+    pub fn update_supplier_kvs(&mut self) -> usize {
         let supplier_aggregate_im = self.gable_state().supplier_aggregate_im;
+        let nft_groups = self.get_supplier_partitioned_kvs();
+        let groups = supplier_aggregate_im
+            .keys()
+            .map(|group| (*group, nft_groups.get(group).map(|nfts| nfts.len()).unwrap_or(0)))
+            .collect::<IndexMap<u64, usize>>();
+        let planned = self.supplier_kvs_batching.plan(&groups.iter().map(|(group, nfts)| (*group, *nfts)).collect::<Vec<_>>());
+
+        // only committed batches are transactions an operator would submit, batches over the limit
+        // are recorded as simulated `update_supplier_kvs_oversized` so they are not charged
+        let mut pending = VecDeque::from(planned);
+        let mut transactions = 0;
+        while let Some(batch) = pending.pop_front() {
+            let manifest = self.update_supplier_kvs_manifest(&batch);
+            let receipt = self.execute_unrecorded_manifest(manifest);
+            if receipt.is_commit_success() {
+                let receipt = self.record_receipt("update_supplier_kvs", false, receipt);
+                transactions += 1;
+                let batch = batch.iter().map(|group| (*group, groups[group])).collect::<Vec<_>>();
+                self.supplier_kvs_batching.learn(&batch, receipt.fee_summary.total_execution_cost_units_consumed);
+            } else if is_limit_error(&receipt) && batch.len() > 1 {
+                self.record_receipt("update_supplier_kvs_oversized", true, receipt);
+                let (first, second) = batch.split_at(batch.len() / 2);
+                pending.push_front(second.to_vec());
+                pending.push_front(first.to_vec());
+            } else {
+                self.record_receipt("update_supplier_kvs", false, receipt).expect_commit_success();
+            }
+        }
+        transactions
+    }
+
+    pub fn update_supplier_kvs_manifest(&self, groups: &[u64]) -> TransactionManifestV1 {
         let mut builder = ManifestBuilder::new()
             .lock_fee(self.account, dec!(10))
             .create_proof_from_account_of_amount(self.gable_owner_account, self.gable_owner_badge, dec!(1));
-        for (group, balances) in supplier_aggregate_im {
-            builder = builder.call_method(self.gable_component, "update_supplier_kvs", (group,));
+        for group in groups {
+            builder = builder.call_method(self.gable_component, "update_supplier_kvs", (*group,));
         }
-        self.test_runner.execute_manifest_without_auth(
-            builder.build()
-        ).expect_commit_success();
+        builder.build()
     }
 
     pub fn claim_xrd(&mut self) {
-        self.test_runner.execute_manifest_without_auth(ManifestBuilder::new()
+        let before = self.unlock_pipeline_state();
+        self.execute_manifest("claim_xrd", ManifestBuilder::new()
             .lock_fee(self.account, dec!(10))
             .create_proof_from_account_of_amount(self.gable_owner_account, self.gable_owner_badge, dec!(1))
             .call_method(self.gable_component, "claim_xrd", (self.gable_validator,))
             .build()
         ).expect_commit_success();
+        let after = self.unlock_pipeline_state();
+        self.unlock_pipeline.record_claim(self.day, &before, &after);
     }
 
     pub fn withdraw_lsu(&mut self, nft: NonFungibleLocalId) {
-        self.test_runner.execute_manifest_without_auth(ManifestBuilder::new()
+        self.try_withdraw_lsu(nft).expect_commit_success();
+    }
+
+    pub fn try_withdraw_lsu(&mut self, nft: NonFungibleLocalId) -> TransactionReceiptV1 {
+        self.execute_manifest("withdraw_lsu", ManifestBuilder::new()
             .lock_fee(self.account, dec!(10))
             .withdraw_non_fungibles_from_account(self.account, self.pool_nft, vec![nft])
             .take_all_from_worktop(self.pool_nft, "nfts")
@@ -305,13 +808,58 @@ impl<'a> GableSimulation<'a> {
             })
             .try_deposit_entire_worktop_or_abort(self.account, None)
             .build()
+        )
+    }
+
+    // moves XRD from the simulation account to the Gable owner account and deposits it as owner liquidity
+    pub fn fund_owner_liquidity(&mut self, amount: Decimal) {
+        self.execute_simulation_manifest("fund_owner_liquidity", ManifestBuilder::new()
+            .lock_fee(self.account, dec!(10))
+            .withdraw_from_account(self.account, XRD, amount)
+            .try_deposit_entire_worktop_or_abort(self.gable_owner_account, None)
+            .build()
         ).expect_commit_success();
+        self.owner_deposit_xrd(amount);
+    }
+
+    // operator step of the recovery plan, recycles LSU of the account as owner stake,
+    // with auth it fails because the validator owner badge proof is not available to the operator
+    pub fn stake_lsu_as_owner_and_start_unlock(&mut self, account: ComponentAddress, amount: Decimal) -> Result<(), String> {
+        let manifest = self.owner_stake_manifest(account, self.lsu, amount);
+        self.execute_owner_stake("stake_lsu_as_owner_and_start_unlock", manifest, false)
     }
 
-    pub fn stake_lsu_as_owner_and_start_unlock(&mut self, account: ComponentAddress, amount: Decimal) {
-        self.test_runner.execute_manifest_without_auth(ManifestBuilder::new()
+    // operator step of the recovery plan, stakes XRD of the account as owner stake, fails with auth like the LSU one
+    pub fn stake_xrd_as_owner_and_start_unlock(&mut self, account: ComponentAddress, amount: Decimal) -> Result<(), String> {
+        let manifest = self.owner_stake_manifest(account, XRD, amount);
+        self.execute_owner_stake("stake_xrd_as_owner_and_start_unlock", manifest, false)
+    }
+
+    // models validator rewards, which are not part of the recovery plan, so it always runs without auth
+    pub fn add_validator_reward(&mut self, amount: Decimal) {
+        let manifest = self.owner_stake_manifest(self.account, XRD, amount);
+        self.execute_owner_stake("add_validator_reward", manifest, true).unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    // stakes XRD (or takes LSU) of the account, locks the LSU as owner stake and starts their unlock through Gable,
+    // which needs a proof of the Gable owner badge. `stake_as_owner` and `lock_owner_stake_units` need a proof of the
+    // validator owner badge, which is kept in Gable's `validator_owner_vault` and no Gable method creates a proof of it
+    fn owner_stake_manifest(&self, account: ComponentAddress, resource: ResourceAddress, amount: Decimal) -> TransactionManifestV1 {
+        let mut builder = ManifestBuilder::new()
             .lock_fee(account, dec!(10))
-            .withdraw_from_account(account, self.lsu, amount)
+            .withdraw_from_account(account, resource, amount);
+        if resource == XRD {
+            builder = builder
+                .take_all_from_worktop(XRD, "xrd")
+                .with_name_lookup(|builder, name_lookup| {
+                    builder.call_method(
+                        self.gable_validator,
+                        "stake_as_owner",
+                        (name_lookup.bucket("xrd"),),
+                    )
+                });
+        }
+        builder
             .take_all_from_worktop(self.lsu, "lsu")
             .with_name_lookup(|builder, name_lookup| {
                 builder.call_method(
@@ -319,39 +867,122 @@ impl<'a> GableSimulation<'a> {
                     "lock_owner_stake_units",
                     (name_lookup.bucket("lsu"),),
                 )
-            })            
+            })
+            .create_proof_from_account_of_amount(self.gable_owner_account, self.gable_owner_badge, dec!(1))
             .call_method(self.gable_component, "start_unlock_owner_stake_units", (amount, self.gable_validator, self.validator_owner_badge.clone()))
             .build()
-        ).expect_commit_success();        
     }
 
-    pub fn stake_xrd_as_owner_and_start_unlock(&mut self, account: ComponentAddress, amount: Decimal) {
-        self.test_runner.execute_manifest_without_auth(ManifestBuilder::new()
+    fn execute_owner_stake(&mut self, operation: &str, manifest: TransactionManifestV1, simulated: bool) -> Result<(), String> {
+        let before = self.unlock_pipeline_state();
+        let receipt = if simulated { self.execute_simulation_manifest(operation, manifest) } else { self.execute_manifest(operation, manifest) };
+        if let Some(error) = receipt_error(&receipt) {
+            return Err(format!("{} failed: {}", operation, error));
+        }
+        let after = self.unlock_pipeline_state();
+        self.unlock_pipeline.record_start(self.day, &before, &after);
+        Ok(())
+    }
+
+    // stakes XRD of the account as a regular staker to get LSU for new deposits, the XRD needed follows the redemption rate
+    pub fn acquire_lsu(&mut self, account: ComponentAddress, lsu_amount: Decimal) {
+        // margin for rounding of the LSU minted by the validator
+        let xrd_amount = lsu_amount * self.get_lsu_redemption_rate() + dec!("0.000001");
+        self.execute_simulation_manifest("acquire_lsu", ManifestBuilder::new()
             .lock_fee(account, dec!(10))
-            .withdraw_from_account(account, XRD, amount)
+            .withdraw_from_account(account, XRD, xrd_amount)
             .take_all_from_worktop(XRD, "xrd")
             .with_name_lookup(|builder, name_lookup| {
                 builder.call_method(
                     self.gable_validator,
-                    "stake_as_owner",
+                    "stake",
                     (name_lookup.bucket("xrd"),),
                 )
             })
+            .try_deposit_entire_worktop_or_abort(account, None)
+            .build()
+        ).expect_commit_success();
+    }
+
+    pub fn get_pool_nfts(&mut self, account: ComponentAddress) -> IndexSet<NonFungibleLocalId> {
+        let mut ret = IndexSet::new();
+        for vault in self.test_runner.get_component_vaults(account, self.pool_nft) {
+            if let Some((_amount, ids)) = self.test_runner.inspect_non_fungible_vault(vault) {
+                ret.extend(ids);
+            }
+        }
+        ret
+    }
+
+    // deposits LSU as a new supplier, returns the pool nft received for it
+    pub fn deposit_lsu(&mut self, account: ComponentAddress, amount: Decimal) -> Result<NonFungibleLocalId, String> {
+        let pool_nfts_before = self.get_pool_nfts(account);
+        let receipt = self.execute_manifest("deposit_lsu", ManifestBuilder::new()
+            .lock_fee(account, dec!(10))
+            .withdraw_from_account(account, self.lsu, amount)
             .take_all_from_worktop(self.lsu, "lsu")
             .with_name_lookup(|builder, name_lookup| {
                 builder.call_method(
-                    self.gable_validator,
-                    "lock_owner_stake_units",
+                    self.gable_component,
+                    "deposit_lsu",
                     (name_lookup.bucket("lsu"),),
                 )
-            })            
-            .call_method(self.gable_component, "start_unlock_owner_stake_units", (amount, self.gable_validator, self.validator_owner_badge.clone()))
+            })
+            .try_deposit_entire_worktop_or_abort(account, None)
             .build()
-        ).expect_commit_success();        
+        );
+        if let Some(error) = receipt_error(&receipt) {
+            return Err(error);
+        }
+        self.get_pool_nfts(account)
+            .into_iter()
+            .find(|nft| !pool_nfts_before.contains(nft))
+            .ok_or("No pool NFT received for deposit".to_string())
     }
 
-    pub fn add_validator_reward(&mut self, amount: Decimal) {
-       self.stake_xrd_as_owner_and_start_unlock(self.account, amount);
+    pub fn get_flash_loan_interest(&mut self, amount: Decimal) -> Decimal {
+        amount * self.gable_state().interest_rate
+    }
+
+    // borrows XRD and repays it with interest in the same transaction, interest is paid from the account
+    pub fn flash_loan(&mut self, account: ComponentAddress, amount: Decimal) -> TransactionReceiptV1 {
+        let interest = self.get_flash_loan_interest(amount);
+        self.flash_loans(account, &[(amount, amount + interest)])
+    }
+
+    // takes all (amount, repayment) loans first and repays them in reverse order in one transaction,
+    // difference between repayments and amounts is paid from the account
+    pub fn flash_loans(&mut self, account: ComponentAddress, loans: &[(Decimal, Decimal)]) -> TransactionReceiptV1 {
+        let borrowed = loans.iter().fold(dec!(0), |sum, (amount, _repayment)| sum + *amount);
+        let repaid = loans.iter().fold(dec!(0), |sum, (_amount, repayment)| sum + *repayment);
+        let mut builder = ManifestBuilder::new()
+            .lock_fee(account, dec!(10));
+        // the transient token of every loan is put into its own bucket right away, so each loan is repaid with its own token
+        for (index, (amount, _repayment)) in loans.iter().enumerate() {
+            builder = builder
+                .call_method(self.gable_component, "get_flashloan", (*amount,))
+                .take_all_from_worktop(self.transient_token, format!("transient_{}", index).as_str());
+        }
+        if repaid > borrowed {
+            builder = builder.withdraw_from_account(account, XRD, repaid - borrowed);
+        }
+        for (index, (_amount, repayment)) in loans.iter().enumerate().rev() {
+            let repayment_bucket = format!("repayment_{}", index);
+            let transient_bucket = format!("transient_{}", index);
+            builder = builder
+                .take_from_worktop(XRD, *repayment, repayment_bucket.as_str())
+                .with_name_lookup(|builder, name_lookup| {
+                    builder.call_method(
+                        self.gable_component,
+                        "repay_flashloan",
+                        (name_lookup.bucket(repayment_bucket.as_str()), name_lookup.bucket(transient_bucket.as_str())),
+                    )
+                });
+        }
+        self.execute_manifest("flash_loan", builder
+            .try_deposit_entire_worktop_or_abort(account, None)
+            .build()
+        )
     }
 
 
@@ -369,20 +1000,41 @@ pub impl<'a> GableSimulationTestRunner<'a> {
         )
     }
 
+    fn execute_manifest_with_auth(
+        &mut self,
+        manifest: TransactionManifestV1,
+        initial_proofs: BTreeSet<NonFungibleGlobalId>,
+    ) -> TransactionReceiptV1 {
+        self.execute_manifest_with_proofs(
+            manifest,
+            initial_proofs,
+            EnabledModules::for_notarized_transaction(),
+        )
+    }
+
     fn execute_manifest_with_enabled_modules(
         &mut self,
         manifest: TransactionManifestV1,
         enabled_modules: EnabledModules,
+    ) -> TransactionReceiptV1 {
+        self.execute_manifest_with_proofs(manifest, Default::default(), enabled_modules)
+    }
+
+    fn execute_manifest_with_proofs(
+        &mut self,
+        manifest: TransactionManifestV1,
+        initial_proofs: BTreeSet<NonFungibleGlobalId>,
+        enabled_modules: EnabledModules,
     ) -> TransactionReceiptV1 {
         let mut execution_config = ExecutionConfig::for_notarized_transaction(
-            NetworkDefinition::mainnet(),
+            crate::network().clone(),
         );
         execution_config.enabled_modules = enabled_modules;
         let nonce = self.next_transaction_nonce();
         let test_transaction = TestTransaction::new_from_nonce(manifest, nonce);
         let prepared_transaction = test_transaction.prepare().unwrap();
         let executable =
-            prepared_transaction.get_executable(Default::default());
+            prepared_transaction.get_executable(initial_proofs);
         self.execute_transaction(
             executable,
             Default::default(),
@@ -390,6 +1042,30 @@ pub impl<'a> GableSimulationTestRunner<'a> {
         )
     }
 
+    // validates signatures and header of a transaction of the configured network and executes it with the default configuration
+    fn execute_raw_notarized_transaction(
+        &mut self,
+        raw_transaction: &RawNotarizedTransaction,
+    ) -> Result<TransactionReceiptV1, String> {
+        let validator = NotarizedTransactionValidator::new(
+            ValidationConfig::default(crate::network().id),
+        );
+        let validated_transaction = validator
+            .validate_from_raw(raw_transaction)
+            .map_err(|error| format!("{:?}", error))?;
+        let executable = validated_transaction.get_executable();
+        if let Some(epoch_range) = executable.epoch_range() {
+            if self.get_current_epoch() < epoch_range.start_epoch_inclusive {
+                self.set_current_epoch(epoch_range.start_epoch_inclusive);
+            }
+        }
+        Ok(self.execute_transaction(
+            executable,
+            Default::default(),
+            ExecutionConfig::for_notarized_transaction(crate::network().clone()),
+        ))
+    }
+
     fn new_account_with_xrd(&mut self) -> ComponentAddress {
         let (public_key, _private_key) = self.new_key_pair();
         let test_account_address =
@@ -406,4 +1082,44 @@ pub impl<'a> GableSimulationTestRunner<'a> {
     
         test_account_address
     }
-}
\ No newline at end of file
+}
+
+// Signature badge of a virtual account, its owner role is satisfied by it as long as the account wasn't securified.
+// Proof is added to the transaction directly, so no private key is needed.
+pub fn virtual_signature_badge(account: ComponentAddress) -> Option<NonFungibleGlobalId> {
+    let node_id = account.as_node_id();
+    let resource_address = match node_id.entity_type()? {
+        EntityType::GlobalVirtualSecp256k1Account => SECP256K1_SIGNATURE_VIRTUAL_BADGE,
+        EntityType::GlobalVirtualEd25519Account => ED25519_SIGNATURE_VIRTUAL_BADGE,
+        _ => return None,
+    };
+    // node id of a virtual account is entity type byte followed by the public key hash
+    let public_key_hash = node_id.as_bytes()[1..].to_vec();
+    Some(NonFungibleGlobalId::new(resource_address, NonFungibleLocalId::bytes(public_key_hash).unwrap()))
+}
+
+pub fn receipt_error(receipt: &TransactionReceiptV1) -> Option<String> {
+    match &receipt.result {
+        TransactionResult::Commit(commit) => match &commit.outcome {
+            TransactionOutcome::Success(_) => None,
+            TransactionOutcome::Failure(error) => Some(format!("{:?}", error)),
+        },
+        TransactionResult::Reject(reject) => Some(format!("{:?}", reject.reason)),
+        TransactionResult::Abort(abort) => Some(format!("{:?}", abort.reason)),
+    }
+}
+
+pub fn print_receipt(receipt: &TransactionReceiptV1) {
+    let fee_summary = &receipt.fee_summary;
+    println!(
+        "-- Receipt: {}, fee: {} XRD, execution cost units: {}",
+        receipt_error(receipt).unwrap_or("success".to_string()),
+        fee_summary.total_cost(),
+        fee_summary.total_execution_cost_units_consumed
+    );
+    if let TransactionResult::Commit(commit) = &receipt.result {
+        for (event_type_identifier, _payload) in &commit.application_events {
+            println!("---- Event: {} emitted by {:?}", event_type_identifier.1, event_type_identifier.0);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+use radix_engine_common::prelude::*;
+use radix_engine_interface::macros::dec;
+
+// How NFT holders react to pool liquidity, the default withdraws every eligible NFT on the day liquidity allows.
+#[derive(Clone, Debug)]
+pub struct HolderBehaviour {
+    // probability that a holder checks the pool on a given day
+    pub check_in_probability: f64,
+    // holders do not withdraw claims smaller than this
+    pub min_claim_xrd: Decimal,
+    // holders of NFTs with at least this much LSU wait until all other active holders have withdrawn
+    pub whale_lsu: Option<Decimal>,
+    // share of holders who never withdraw (inactive or lost keys)
+    pub inactive_share: f64,
+}
+
+impl Default for HolderBehaviour {
+    fn default() -> Self {
+        HolderBehaviour {
+            check_in_probability: 1.0,
+            min_claim_xrd: dec!(0),
+            whale_lsu: None,
+            inactive_share: 0.0,
+        }
+    }
+}
+
+// number of times a withdrawable NFT was not withdrawn, by reason
+#[derive(Clone, Debug, Default)]
+pub struct HolderDeferrals {
+    pub away: u64,
+    pub small_claim: u64,
+    pub whale: u64,
+}
+
+pub struct HolderModel {
+    behaviour: HolderBehaviour,
+    rng: StdRng,
+    // holders are drawn active or inactive the first time their NFT is seen
+    inactive: IndexMap<NonFungibleLocalId, bool>,
+    pub deferrals: HolderDeferrals,
+}
+
+impl HolderModel {
+    pub fn new(behaviour: HolderBehaviour, rng: StdRng) -> Self {
+        HolderModel { behaviour, rng, inactive: IndexMap::new(), deferrals: HolderDeferrals::default() }
+    }
+
+    fn is_inactive(&mut self, nft: &NonFungibleLocalId) -> bool {
+        if let Some(inactive) = self.inactive.get(nft) {
+            return *inactive;
+        }
+        let inactive = self.behaviour.inactive_share > 0.0 && self.rng.gen_bool(self.behaviour.inactive_share.min(1.0));
+        self.inactive.insert(nft.clone(), inactive);
+        inactive
+    }
+
+    fn is_whale(&self, lsu: Decimal) -> bool {
+        self.behaviour.whale_lsu.is_some_and(|whale_lsu| lsu >= whale_lsu)
+    }
+
+    // all remaining NFTs belong to inactive holders
+    pub fn is_recovered(&mut self, claims: &IndexMap<NonFungibleLocalId, (Decimal, Decimal)>) -> bool {
+        claims.keys().all(|nft| self.is_inactive(nft))
+    }
+
+    // LSU and XRD claims of NFTs of inactive holders, which are never withdrawn
+    pub fn inactive_claims(&mut self, claims: &IndexMap<NonFungibleLocalId, (Decimal, Decimal)>) -> (usize, Decimal, Decimal) {
+        claims
+            .iter()
+            .filter(|(nft, _amounts)| self.is_inactive(nft))
+            .fold((0, dec!(0), dec!(0)), |(count, lsu_sum, xrd_sum), (_nft, (lsu, xrd))| (count + 1, lsu_sum + *lsu, xrd_sum + *xrd))
+    }
+
+    // NFTs whose holders withdraw today if pool liquidity allows
+    pub fn willing(&mut self, claims: IndexMap<NonFungibleLocalId, (Decimal, Decimal)>) -> IndexMap<NonFungibleLocalId, (Decimal, Decimal)> {
+        let active = claims.into_iter().filter(|(nft, _amounts)| !self.is_inactive(nft)).collect::<IndexMap<_, _>>();
+        let others_left = active.values().any(|(lsu, _xrd)| !self.is_whale(*lsu));
+        let mut willing = IndexMap::new();
+        for (nft, (lsu, xrd)) in active {
+            if others_left && self.is_whale(lsu) {
+                self.deferrals.whale += 1;
+                continue;
+            }
+            if xrd < self.behaviour.min_claim_xrd {
+                self.deferrals.small_claim += 1;
+                continue;
+            }
+            if self.behaviour.check_in_probability < 1.0 && !self.rng.gen_bool(self.behaviour.check_in_probability.max(0.0)) {
+                self.deferrals.away += 1;
+                continue;
+            }
+            willing.insert(nft, (lsu, xrd));
+        }
+        willing
+    }
+}
+
+impl HolderDeferrals {
+    pub fn print_summary(&self) {
+        if self.away + self.small_claim + self.whale == 0 {
+            return;
+        }
+        println!("Withdrawals deferred by holders, not checked in: {}, claim too small: {}, whale waiting: {}", self.away, self.small_claim, self.whale);
+    }
+}
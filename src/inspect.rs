@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use radix_engine_common::prelude::*;
+use radix_engine_interface::blueprints::resource::OWNER_ROLE;
+
+use crate::gable_simulation::GableSimulation;
+use crate::provenance;
+
+pub fn print_state(simulation: &mut GableSimulation) {
+    let gable_state = simulation.gable_state();
+    let validator_state = simulation.validator_state();
+    let epoch = simulation.test_runner.get_current_epoch();
+    println!("Epoch: {}", epoch.number());
+    println!("Gable component:");
+    println!("-- XRD in liquidity pool vault: {}", simulation.get_xrd_balance());
+    println!("-- LSU locked in contract: {}", simulation.get_lsu_balance());
+    println!("-- Owner liquidity: {}", gable_state.owner_liquidity);
+    println!("-- Pool liquidity: {}", simulation.get_pool_liqudity());
+    println!("-- Rewards liquidity: {}", gable_state.rewards_liquidity);
+    println!("-- Interest rate: {}", gable_state.interest_rate);
+    println!("-- Box size: {}", gable_state.box_size);
+    println!("-- Pool NFTs minted: {}", gable_state.pool_nft_nr);
+    println!("-- Supplier groups: {}", gable_state.supplier_aggregate_im.len());
+    println!("-- Unstake claim NFTs: {}", gable_state.nft_vec.len());
+    println!("Validator:");
+    println!("-- Registered: {}", validator_state.is_registered);
+    println!("-- Fee factor: {}", validator_state.validator_fee_factor);
+    println!("-- Already unlocked owner LSU: {}", validator_state.already_unlocked_owner_stake_unit_amount);
+    for (epoch, amount) in &validator_state.pending_owner_stake_unit_withdrawals {
+        println!("-- Pending owner unlock of {} LSU at epoch {}", amount, epoch.number());
+    }
+}
+
+// one line per configured pool
+pub fn print_pool_summary(simulation: &mut GableSimulation, name: &str) {
+    let gable_state = simulation.gable_state();
+    let encoder = AddressBech32Encoder::new(crate::network());
+    println!(
+        "{}: component {}, validator {}, pool liquidity: {}, owner liquidity: {}, LSU locked: {}, pool NFTs minted: {}, LSU redemption rate: {}",
+        name,
+        encoder.encode(simulation.gable_component.as_node_id().as_bytes()).unwrap(),
+        encoder.encode(simulation.gable_validator.as_node_id().as_bytes()).unwrap(),
+        simulation.get_pool_liqudity(),
+        gable_state.owner_liquidity,
+        simulation.get_lsu_balance(),
+        gable_state.pool_nft_nr,
+        simulation.get_lsu_redemption_rate()
+    );
+}
+
+// role protected methods of the Gable blueprint and the badge `call_protected` presents for them
+pub fn print_protected_methods(simulation: &mut GableSimulation) {
+    let methods = match simulation.gable_protected_methods() {
+        Ok(methods) => methods,
+        Err(error) => {
+            println!("Can't read the Gable blueprint definition: {}", error);
+            return;
+        }
+    };
+    let mut without_wrapper = Vec::new();
+    for (method, roles) in &methods {
+        let badge = if roles.iter().any(|role| role == OWNER_ROLE) { "owner badge" } else { "admin badge" };
+        let wrapper = if GableSimulation::PROTECTED_METHOD_WRAPPERS.contains(&method.as_str()) { "typed wrapper" } else { "no typed wrapper" };
+        println!("-- {}: roles {}, called with the {}, {}", method, roles.join(", "), badge, wrapper);
+        if wrapper == "no typed wrapper" {
+            without_wrapper.push(method.clone());
+        }
+    }
+    if !without_wrapper.is_empty() {
+        println!("Methods without a typed wrapper can only be called with `call_protected`: {}", without_wrapper.join(", "));
+    }
+    for method in GableSimulation::PROTECTED_METHOD_WRAPPERS {
+        if !methods.contains_key(method) {
+            println!("Wrapped method `{}` is not a protected method of the deployed blueprint", method);
+        }
+    }
+}
+
+pub fn print_nfts(simulation: &mut GableSimulation) {
+    let mut nfts = Vec::new();
+    for (group, group_nfts) in simulation.get_supplier_partitioned_kvs() {
+        for (nft, amounts) in group_nfts {
+            nfts.push((group, nft, amounts[0], amounts[1] + amounts[2]));
+        }
+    }
+    nfts.sort_by(|(_, _, lsu1, _), (_, _, lsu2, _)| lsu2.cmp(lsu1));
+    for (group, nft, lsu, xrd) in &nfts {
+        println!("-- Group: {}, NFT: {:?} with LSU: {} and XRD claim: {}", group, nft, lsu, xrd);
+    }
+    println!("NFTs: {}", nfts.len());
+}
+
+// writes supplier nfts and pool state to csv files which can be used without the database
+pub fn extract_fixture(simulation: &mut GableSimulation, output_dir: &Path) {
+    let mut csv = provenance::csv_writer(&output_dir.join("fixture_nfts.csv"));
+    csv.write_record(["Group", "NFT", "LSU", "XRD claim"]).unwrap();
+    for (group, group_nfts) in simulation.get_supplier_partitioned_kvs() {
+        for (nft, amounts) in group_nfts {
+            csv.write_record(&[group.to_string(), nft.to_string(), amounts[0].to_string(), (amounts[1] + amounts[2]).to_string()]).unwrap();
+        }
+    }
+    csv.flush().unwrap();
+
+    let gable_state = simulation.gable_state();
+    let mut csv = provenance::csv_writer(&output_dir.join("fixture_pool_state.csv"));
+    csv.write_record(["Field", "Value"]).unwrap();
+    let fields = [
+        ("epoch", simulation.test_runner.get_current_epoch().number().to_string()),
+        ("xrd_balance", simulation.get_xrd_balance().to_string()),
+        ("lsu_balance", simulation.get_lsu_balance().to_string()),
+        ("owner_liquidity", gable_state.owner_liquidity.to_string()),
+        ("rewards_liquidity", gable_state.rewards_liquidity.to_string()),
+        ("interest_rate", gable_state.interest_rate.to_string()),
+        ("box_size", gable_state.box_size.to_string()),
+    ];
+    for (field, value) in fields {
+        csv.write_record(&[field.to_string(), value]).unwrap();
+    }
+    for (epoch, amount) in simulation.get_pending_unstakes() {
+        csv.write_record(&[format!("pending_unstake_{}", epoch.number()), amount.to_string()]).unwrap();
+    }
+    csv.flush().unwrap();
+}
@@ -1,18 +1,95 @@
+pub mod batching;
+pub mod checkpoint;
+pub mod diagnostics;
+pub mod environment;
+pub mod estimator;
+pub mod events;
+pub mod fees;
+pub mod flash_loan_stress;
 mod gable_simulation;
+pub mod holders;
+pub mod inspect;
+pub mod manifests;
+pub mod pools;
+pub mod provenance;
+pub mod repl;
+pub mod replay;
+pub mod report;
+pub mod scenarios;
+pub mod scheduler;
+pub mod state_diff;
 mod structures;
+pub mod sweep;
+pub mod unlock_pipeline;
+pub mod withdrawals;
 
 use core::panic;
+use std::path::PathBuf;
 use std::sync::OnceLock;
 
-use gable_simulation::GableSimulation;
+use gable_simulation::GableSimulationTestRunner;
+use environment::Environment;
+use pools::PoolConfig;
+use provenance::SnapshotMetadata;
+use radix_engine_common::prelude::NetworkDefinition;
+pub use gable_simulation::{print_receipt, receipt_error, AuthMode, GableSimulation};
 use scrypto_test::ledger_simulator::LedgerSimulatorBuilder;
 use substate_store_impls::{rocks_db::RocksdbSubstateStore, substate_database_overlay::UnmergeableSubstateDatabaseOverlay};
 
+static DATABASE_PATH: OnceLock<PathBuf> = OnceLock::new();
+static ENVIRONMENT: OnceLock<Environment> = OnceLock::new();
+static STATE_VERSION: OnceLock<u64> = OnceLock::new();
+
+// overrides `STATE_MANAGER_DATABASE_PATH`, has to be called before the first simulation is created
+pub fn set_database_path(path: PathBuf) {
+    if DATABASE_PATH.set(path).is_err() {
+        panic!("The database path is already set");
+    }
+}
+
+// selects network and pool instead of the Gable pool on mainnet, has to be called before the first simulation is created
+pub fn set_environment(environment: Environment) {
+    if ENVIRONMENT.set(environment).is_err() {
+        panic!("The environment is already set");
+    }
+}
+
+fn get_environment() -> &'static Environment {
+    ENVIRONMENT.get_or_init(Environment::default)
+}
+
+// network used to decode and encode addresses and to validate and execute transactions
+pub fn network() -> &'static NetworkDefinition {
+    &get_environment().network
+}
+
+pub(crate) fn get_pool() -> &'static PoolConfig {
+    &get_environment().pool
+}
+
+// state version of the snapshot as given by the user, it is not part of the substates so it is stamped unchecked
+pub fn set_state_version(state_version: u64) {
+    if STATE_VERSION.set(state_version).is_err() {
+        panic!("The state version is already set");
+    }
+}
+
+// metadata of the snapshot database, read once on a fresh fork
+pub fn snapshot_metadata() -> &'static SnapshotMetadata {
+    static METADATA: OnceLock<SnapshotMetadata> = OnceLock::new();
+    METADATA.get_or_init(|| {
+        execute_within_environment(|mut simulation| SnapshotMetadata::capture(&mut simulation, &get_pool().name, STATE_VERSION.get().cloned()))
+    })
+}
+
 fn get_database() -> &'static RocksdbSubstateStore {
     static DATABASE: OnceLock<RocksdbSubstateStore> = OnceLock::new();
     DATABASE.get_or_init(|| {
         const STATE_MANAGER_DATABASE_PATH_ENVIRONMENT_VARIABLE: &str =
             "STATE_MANAGER_DATABASE_PATH";
+        if let Some(state_manager_database_path) = DATABASE_PATH.get() {
+            return RocksdbSubstateStore::read_only(state_manager_database_path.clone());
+        }
         let Ok(state_manager_database_path) =
             std::env::var(STATE_MANAGER_DATABASE_PATH_ENVIRONMENT_VARIABLE)
                 .map(std::path::PathBuf::from)
@@ -30,14 +107,41 @@ pub fn execute_within_environment<'a, F, O>(test_function: F) -> O
 where
     F: Fn(GableSimulation<'a>) -> O,
 {
-    let state_manager = get_database();
+    let simulation = GableSimulation::new(new_test_runner(), get_pool());
+    test_function(simulation)
+}
+
+// simulation of a pool other than the selected one on a fresh fork of the snapshot, e.g. to list all configured pools
+pub fn execute_within_pool<F, O>(pool: &PoolConfig, test_function: F) -> O
+where
+    F: for<'a> FnOnce(GableSimulation<'a>) -> O,
+{
+    let simulation = GableSimulation::new(new_test_runner(), pool);
+    test_function(simulation)
+}
+
+// simulation on a database other than the configured snapshot, e.g. a later snapshot to compare against
+pub fn execute_within_database<F, O>(state_manager_database_path: PathBuf, test_function: F) -> O
+where
+    F: for<'a> FnOnce(GableSimulation<'a>) -> O,
+{
+    let state_manager = RocksdbSubstateStore::read_only(state_manager_database_path);
+    let simulation = GableSimulation::new(new_test_runner_on(&state_manager), get_pool());
+    test_function(simulation)
+}
+
+// fresh fork of the snapshot database
+pub(crate) fn new_test_runner<'a>() -> GableSimulationTestRunner<'a> {
+    new_test_runner_on(get_database())
+}
+
+fn new_test_runner_on(state_manager: &RocksdbSubstateStore) -> GableSimulationTestRunner<'_> {
     let database =
         UnmergeableSubstateDatabaseOverlay::new_unmergeable(state_manager);
     
-    let test_runner: scrypto_test::prelude::LedgerSimulator<scrypto_test::prelude::NoExtension, substate_store_impls::substate_database_overlay::SubstateDatabaseOverlay<&RocksdbSubstateStore, RocksdbSubstateStore>> = LedgerSimulatorBuilder::new()
+    let test_runner: GableSimulationTestRunner<'_> = LedgerSimulatorBuilder::new()
         .with_custom_database(database)
         .without_kernel_trace()
         .build_without_bootstrapping();
-    let simulation = GableSimulation::new(test_runner);
-    test_function(simulation)
+    test_runner
 }
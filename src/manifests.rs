@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use radix_engine_common::prelude::*;
+use transaction::manifest::{compile, BlobProvider};
+use transaction::model::TransactionManifestV1;
+
+use crate::gable_simulation::GableSimulation;
+
+// Named placeholders available in `.rtm` files, written as `${name}`.
+// Addresses are substituted as `Address("...")` and the validator owner badge id as `NonFungibleLocalId("...")`.
+pub fn placeholders(simulation: &GableSimulation) -> IndexMap<String, String> {
+    let encoder = AddressBech32Encoder::new(crate::network());
+    let address = |node_id: &NodeId| format!("Address(\"{}\")", encoder.encode(node_id.as_bytes()).unwrap());
+    let mut placeholders = IndexMap::new();
+    placeholders.insert("gable_component".to_string(), address(simulation.gable_component.as_node_id()));
+    placeholders.insert("gable_validator".to_string(), address(simulation.gable_validator.as_node_id()));
+    placeholders.insert("gable_owner_account".to_string(), address(simulation.gable_owner_account.as_node_id()));
+    placeholders.insert("gable_owner_badge".to_string(), address(simulation.gable_owner_badge.as_node_id()));
+    placeholders.insert("admin_badge".to_string(), address(simulation.admin_badge.as_node_id()));
+    placeholders.insert("validator_owner_badge".to_string(), format!("NonFungibleLocalId(\"{}\")", simulation.validator_owner_badge));
+    placeholders.insert("lsu".to_string(), address(simulation.lsu.as_node_id()));
+    placeholders.insert("pool_nft".to_string(), address(simulation.pool_nft.as_node_id()));
+    placeholders.insert("account".to_string(), address(simulation.account.as_node_id()));
+    placeholders.insert("xrd".to_string(), address(XRD.as_node_id()));
+    placeholders
+}
+
+// replaces placeholders of the simulation and given variables, variables take precedence
+pub fn substitute_placeholders(simulation: &GableSimulation, text: &str, variables: &IndexMap<String, String>) -> Result<String, String> {
+    let mut values = placeholders(simulation);
+    values.extend(variables.iter().map(|(name, value)| (name.clone(), value.clone())));
+
+    let mut ret = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            return Err("Unterminated placeholder".to_string());
+        };
+        let name = &rest[start + 2..start + end];
+        let Some(value) = values.get(name) else {
+            return Err(format!("Unknown placeholder `{}`", name));
+        };
+        ret.push_str(&rest[..start]);
+        ret.push_str(value);
+        rest = &rest[start + end + 1..];
+    }
+    ret.push_str(rest);
+    Ok(ret)
+}
+
+pub fn compile_manifest(simulation: &GableSimulation, text: &str, variables: &IndexMap<String, String>) -> Result<TransactionManifestV1, String> {
+    let text = substitute_placeholders(simulation, text, variables)?;
+    compile(&text, crate::network(), BlobProvider::new())
+        .map_err(|error| format!("Failed to compile manifest: {:?}", error))
+}
+
+pub fn load_manifest(simulation: &GableSimulation, path: &Path, variables: &IndexMap<String, String>) -> Result<TransactionManifestV1, String> {
+    let text = std::fs::read_to_string(path).map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+    compile_manifest(simulation, &text, variables)
+}
@@ -0,0 +1,143 @@
+use std::path::Path;
+
+use radix_engine_common::prelude::*;
+
+// Addresses of a Gable pool and the validator it stakes to, as configured (bech32 or local id strings).
+// Owner badge, LSU and validator owner badge are read from the ledger if not set.
+#[derive(Clone, Debug, Default)]
+pub struct PoolConfig {
+    pub name: String,
+    pub component: String,
+    pub validator: String,
+    pub owner_account: String,
+    pub owner_badge: Option<String>,
+    pub lsu: Option<String>,
+    pub validator_owner_badge: Option<String>,
+}
+
+// decoded addresses of `PoolConfig`, optional ones are resolved by `GableSimulation`
+#[derive(Clone, Debug)]
+pub struct PoolAddresses {
+    pub component: ComponentAddress,
+    pub validator: ComponentAddress,
+    pub owner_account: ComponentAddress,
+    pub owner_badge: Option<ResourceAddress>,
+    pub lsu: Option<ResourceAddress>,
+    pub validator_owner_badge: Option<NonFungibleLocalId>,
+}
+
+impl PoolConfig {
+    // the Gable pool on mainnet, used if no pool is configured
+    pub fn gable_mainnet() -> Self {
+        PoolConfig {
+            name: "gable".to_string(),
+            component: "component_rdx1cpmh7lyg0hx6efv5q79lv6rqxdqpuh27y99nzm0jpwu2u44ne243ws".to_string(),
+            validator: "validator_rdx1sdf04wxuc7c4llwst8rw5sfj350gnlnluhrpy09wk2gwk5cmvgffpy".to_string(),
+            owner_account: "account_rdx12y0frj9mjxmlc36gggsts826jsp2e6wk40tv85prpve32kx2u360y3".to_string(),
+            owner_badge: Some("resource_rdx1t4zd2h95htm79dmyr9d422qy4c03urvkutqxgsyxx9udcmrdgk9s22".to_string()),
+            lsu: Some("resource_rdx1thrz4g8g83802lumrtrdsrhjd6k5uxhxhgkrwjg0jn75cvxfc99nap".to_string()),
+            validator_owner_badge: Some("[8352fab8dcc7b15ffdd059c6ea41328d1e89fe7fe5c6123caeb290eb531b]".to_string()),
+        }
+    }
+
+    pub fn decode(&self, decoder: &AddressBech32Decoder) -> Result<PoolAddresses, String> {
+        let component = |field: &str, value: &str| {
+            ComponentAddress::try_from_bech32(decoder, value).ok_or_else(|| format!("pool `{}`: invalid {} address `{}`", self.name, field, value))
+        };
+        let resource = |field: &str, value: &str| {
+            ResourceAddress::try_from_bech32(decoder, value).ok_or_else(|| format!("pool `{}`: invalid {} address `{}`", self.name, field, value))
+        };
+        Ok(PoolAddresses {
+            component: component("component", &self.component)?,
+            validator: component("validator", &self.validator)?,
+            owner_account: component("owner_account", &self.owner_account)?,
+            owner_badge: self.owner_badge.as_deref().map(|value| resource("owner_badge", value)).transpose()?,
+            lsu: self.lsu.as_deref().map(|value| resource("lsu", value)).transpose()?,
+            validator_owner_badge: self
+                .validator_owner_badge
+                .as_deref()
+                .map(|value| NonFungibleLocalId::from_str(value).map_err(|_| format!("pool `{}`: invalid validator_owner_badge `{}`", self.name, value)))
+                .transpose()?,
+        })
+    }
+}
+
+// Pools which can be simulated, selected by name, configured in an environment profile.
+#[derive(Clone, Debug)]
+pub struct PoolRegistry {
+    pub pools: Vec<PoolConfig>,
+}
+
+impl Default for PoolRegistry {
+    fn default() -> Self {
+        PoolRegistry { pools: vec![PoolConfig::gable_mainnet()] }
+    }
+}
+
+impl PoolRegistry {
+    // Reads pools from a file with a `[name]` section per pool and `key = value` lines, `#` starts a comment:
+    //   [gable]
+    //   component = component_rdx1...
+    //   validator = validator_rdx1...
+    //   owner_account = account_rdx1...
+    // `owner_badge`, `lsu` and `validator_owner_badge` are optional.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        Self::parse(&text).map_err(|error| format!("{}: {}", path.display(), error))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        Self::parse_lines(text.lines().enumerate())
+    }
+
+    // sections of a file with their 0-based line numbers, also used for the pools of an environment profile
+    pub fn parse_lines<'a>(lines: impl Iterator<Item = (usize, &'a str)>) -> Result<Self, String> {
+        let mut pools: Vec<PoolConfig> = Vec::new();
+        for (index, line) in lines {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                let name = name.trim();
+                if pools.iter().any(|pool| pool.name == name) {
+                    return Err(format!("line {}: duplicate pool `[{}]`", index + 1, name));
+                }
+                pools.push(PoolConfig { name: name.to_string(), ..Default::default() });
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {}: expected `key = value`", index + 1));
+            };
+            let Some(pool) = pools.last_mut() else {
+                return Err(format!("line {}: `{}` outside of a `[pool]` section", index + 1, key.trim()));
+            };
+            let value = value.trim().to_string();
+            match key.trim() {
+                "component" => pool.component = value,
+                "validator" => pool.validator = value,
+                "owner_account" => pool.owner_account = value,
+                "owner_badge" => pool.owner_badge = Some(value),
+                "lsu" => pool.lsu = Some(value),
+                "validator_owner_badge" => pool.validator_owner_badge = Some(value),
+                key => return Err(format!("line {}: unknown key `{}`", index + 1, key)),
+            }
+        }
+        for pool in &pools {
+            for (key, value) in [("component", &pool.component), ("validator", &pool.validator), ("owner_account", &pool.owner_account)] {
+                if value.is_empty() {
+                    return Err(format!("pool `{}`: `{}` is not set", pool.name, key));
+                }
+            }
+        }
+        Ok(PoolRegistry { pools })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PoolConfig> {
+        self.pools.iter().find(|pool| pool.name == name)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.pools.iter().map(|pool| pool.name.as_str()).collect()
+    }
+}
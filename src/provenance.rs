@@ -0,0 +1,119 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use radix_engine_common::prelude::*;
+use radix_engine_derive::ScryptoSbor;
+
+use crate::gable_simulation::GableSimulation;
+
+// Ledger state of the snapshot all simulations fork from, stamped on every output file and checkpoint.
+#[derive(Clone, Debug, ScryptoSbor)]
+pub struct SnapshotMetadata {
+    pub network: String,
+    pub pool: String,
+    // not stored in substates, given by the user with `--state-version` and not checked against the database
+    pub state_version: Option<u64>,
+    pub epoch: u64,
+    pub round: u64,
+    pub proposer_timestamp_ms: i64,
+    // hash of Gable component state, supplier entries and vault balances
+    pub gable_state_hash: Hash,
+}
+
+impl SnapshotMetadata {
+    // has to be captured on a fresh fork, before the simulation changes anything
+    pub fn capture(simulation: &mut GableSimulation, pool: &str, state_version: Option<u64>) -> Self {
+        let (epoch, round) = simulation.consensus_manager_round();
+        SnapshotMetadata {
+            network: crate::network().logical_name.clone(),
+            pool: pool.to_string(),
+            state_version,
+            epoch,
+            round,
+            proposer_timestamp_ms: simulation.proposer_milli_timestamp(),
+            gable_state_hash: gable_state_hash(simulation),
+        }
+    }
+
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("network", self.network.clone()),
+            ("pool", self.pool.clone()),
+            ("state version (user-supplied)", self.state_version.map(|state_version| state_version.to_string()).unwrap_or("not given".to_string())),
+            ("epoch", self.epoch.to_string()),
+            ("round", self.round.to_string()),
+            ("proposer timestamp", format!("{} ({} ms)", format_timestamp(self.proposer_timestamp_ms), self.proposer_timestamp_ms)),
+            ("gable state hash", self.gable_state_hash.to_string()),
+        ]
+    }
+
+    pub fn print(&self) {
+        println!("Snapshot:");
+        for (name, value) in self.fields() {
+            println!("-- {}: {}", name, value);
+        }
+    }
+}
+
+fn gable_state_hash(simulation: &mut GableSimulation) -> Hash {
+    let gable_state = simulation.gable_state();
+    let mut encoded = scrypto_encode(&gable_state).unwrap();
+    encoded.extend(scrypto_encode(&simulation.get_supplier_partitioned_kvs()).unwrap());
+    let vaults = [
+        &gable_state.liquidity_pool_vault,
+        &gable_state.lsu_vault,
+        &gable_state.validator_owner_vault,
+        &gable_state.unstaking_lsu_vault,
+        &gable_state.unstaking_nft_vault,
+    ];
+    for vault in vaults {
+        let balance = simulation.test_runner.inspect_vault_balance(*vault.0.as_node_id()).unwrap_or_default();
+        encoded.extend(scrypto_encode(&balance).unwrap());
+    }
+    hash(encoded)
+}
+
+// UTC date and time of a unix timestamp in milliseconds
+fn format_timestamp(timestamp_ms: i64) -> String {
+    let seconds = timestamp_ms.div_euclid(1000);
+    let (days, seconds_of_day) = (seconds.div_euclid(86400), seconds.rem_euclid(86400));
+    // civil date from days since 1970-01-01
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC", year, month, day, seconds_of_day / 3600, seconds_of_day % 3600 / 60, seconds_of_day % 60)
+}
+
+// csv file starting with `#` comment lines of the snapshot metadata, read back with `csv_reader`
+pub fn csv_writer(path: &Path) -> csv::Writer<File> {
+    let mut file = File::create(path).unwrap();
+    for (name, value) in crate::snapshot_metadata().fields() {
+        writeln!(file, "# {}: {}", name, value).unwrap();
+    }
+    csv::Writer::from_writer(file)
+}
+
+pub fn csv_reader(path: &Path) -> csv::Result<csv::Reader<File>> {
+    csv::ReaderBuilder::new().comment(Some(b'#')).from_path(path)
+}
+
+// snapshot metadata lines of a csv file written by `csv_writer`
+pub fn read_csv_stamp(path: &Path) -> Vec<String> {
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    text.lines().take_while(|line| line.starts_with("# ")).map(|line| line[2..].to_string()).collect()
+}
+
+// xml comment with the snapshot metadata, placed after the opening `<svg>` tag
+pub fn svg_comment() -> String {
+    let fields = crate::snapshot_metadata().fields().into_iter().map(|(name, value)| format!("{}: {}", name, value)).collect::<Vec<_>>();
+    format!("<!-- {} -->\n", fields.join(", "))
+}
@@ -0,0 +1,160 @@
+use std::io::{BufRead, Write};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use radix_engine_common::prelude::*;
+
+use crate::gable_simulation::{print_receipt, receipt_error, AuthMode, GableSimulation, SimulationSnapshot};
+use crate::inspect;
+use crate::state_diff::{self, StateView};
+
+const HELP: &str = "Commands:
+  advance <epochs>     move time by given number of epochs (288 epochs is around 24h)
+  reward <xrd>         add validator reward
+  finish               finish matured owner unlocks and unstake
+  claim                claim XRD from matured unstakes
+  update               recalculate user rewards (update_supplier_kvs)
+  claims [all]         list NFT claims, without the last NFT of each group unless `all`
+  withdraw <nft>       withdraw given NFT, e.g. `withdraw #513#`
+  deposit <lsu>        deposit LSU as a new supplier
+  flashloan <xrd>      take a flash loan and repay it with interest
+  state                show pool and validator state
+  unstakes             show pending owner unlocks and unstakes
+  lifecycle            show unstake claim NFTs from the unlocks which created them to the XRD delivered
+  receipts on|off      print fee and events of each receipt
+  auth on|off          execute recovery plan operations with auth checks
+  diff on|off          print changes of Gable and validator state after each step
+  undo                 revert the previous step
+  help                 show this message
+  exit                 leave the shell";
+
+pub fn run(simulation: &mut GableSimulation) {
+    let mut history: Vec<SimulationSnapshot> = Vec::new();
+    let mut print_receipts = true;
+    let mut print_diff = false;
+    simulation.keep_receipts = true;
+
+    println!("{}", HELP);
+    let stdin = std::io::stdin();
+    loop {
+        print!("epoch {}> ", simulation.test_runner.get_current_epoch().number());
+        std::io::stdout().flush().unwrap();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        let args = line.split_whitespace().collect::<Vec<_>>();
+        let Some((command, args)) = args.split_first() else {
+            continue;
+        };
+
+        match *command {
+            "exit" | "quit" => break,
+            "help" => println!("{}", HELP),
+            "receipts" => print_receipts = args.first() != Some(&"off"),
+            "diff" => print_diff = args.first() != Some(&"off"),
+            "auth" => {
+                simulation.auth_mode = if args.first() == Some(&"off") { AuthMode::Disabled } else { AuthMode::Enabled };
+                println!("Auth: {:?}", simulation.auth_mode);
+            }
+            "undo" => match history.pop() {
+                Some(snapshot) => {
+                    simulation.restore(&snapshot);
+                    println!("Reverted to epoch {}", simulation.test_runner.get_current_epoch().number());
+                }
+                None => println!("Nothing to undo"),
+            },
+            "claims" => print_claims(simulation, args.first() == Some(&"all")),
+            "state" => inspect::print_state(simulation),
+            "unstakes" => print_unstakes(simulation),
+            "lifecycle" => simulation.unlock_pipeline.print_claim_lifecycle(),
+            _ => {
+                let snapshot = simulation.snapshot();
+                let state_before = print_diff.then(|| StateView::capture(simulation));
+                let result = catch_unwind(AssertUnwindSafe(|| execute_step(simulation, command, args)));
+                let receipts = std::mem::take(&mut simulation.receipts);
+                if print_receipts {
+                    receipts.iter().for_each(print_receipt);
+                }
+                match result {
+                    Ok(Ok(())) => {
+                        if let Some(state_before) = state_before {
+                            state_diff::print_diff(&state_before, &StateView::capture(simulation));
+                        }
+                        history.push(snapshot);
+                    }
+                    Ok(Err(error)) => {
+                        println!("{}", error);
+                        simulation.restore(&snapshot);
+                    }
+                    Err(_) => {
+                        println!("Step failed, state was reverted");
+                        simulation.restore(&snapshot);
+                    }
+                }
+            }
+        }
+    }
+
+    simulation.keep_receipts = false;
+}
+
+fn execute_step(simulation: &mut GableSimulation, command: &str, args: &[&str]) -> Result<(), String> {
+    match command {
+        "advance" => simulation.add_epoch(parse_arg(args)?),
+        "reward" => simulation.add_validator_reward(parse_arg(args)?),
+        "finish" => simulation.finish_unlock_and_unstake(),
+        "claim" => simulation.claim_xrd(),
+        "update" => {
+            let transactions = simulation.update_supplier_kvs();
+            println!("User rewards recalculated in {} transactions", transactions);
+        }
+        "withdraw" => {
+            let nft: NonFungibleLocalId = parse_arg(args)?;
+            simulation.acquire_nft(simulation.account, nft.clone());
+            let receipt = simulation.try_withdraw_lsu(nft);
+            if let Some(error) = receipt_error(&receipt) {
+                return Err(format!("Withdraw failed: {}", error));
+            }
+        }
+        "deposit" => {
+            let amount: Decimal = parse_arg(args)?;
+            simulation.acquire_lsu(simulation.account, amount);
+            let nft = simulation.deposit_lsu(simulation.account, amount).map_err(|error| format!("Deposit failed: {}", error))?;
+            println!("Received NFT: {:?}", nft);
+        }
+        "flashloan" => {
+            let receipt = simulation.flash_loan(simulation.account, parse_arg(args)?);
+            if let Some(error) = receipt_error(&receipt) {
+                return Err(format!("Flash loan failed: {}", error));
+            }
+        }
+        _ => return Err(format!("Unknown command `{}`, type `help` for the list of commands", command)),
+    }
+    Ok(())
+}
+
+fn parse_arg<T: FromStr>(args: &[&str]) -> Result<T, String> {
+    let arg = args.first().ok_or("Missing argument".to_string())?;
+    T::from_str(arg).map_err(|_| format!("Invalid argument `{}`", arg))
+}
+
+fn print_claims(simulation: &mut GableSimulation, all: bool) {
+    let owner_liqudity = simulation.get_owner_liqudity();
+    let pool_liqudity = simulation.get_pool_liqudity();
+    let mut nfts = simulation.get_lsu_claims(!all);
+    nfts.sort_by(|_, v1, _, v2| v2.0.cmp(&v1.0));
+    for (nft, (lsu, xrd)) in &nfts {
+        let withdrawable = *xrd <= pool_liqudity + owner_liqudity;
+        println!("-- NFT: {:?} with LSU: {} and XRD claim: {}{}", nft, lsu, xrd, if withdrawable { "" } else { " (not enough liquidity)" });
+    }
+    println!("NFTs: {}, pool liquidity: {}, owner liquidity: {}", nfts.len(), pool_liqudity, owner_liqudity);
+}
+
+fn print_unstakes(simulation: &mut GableSimulation) {
+    for (epoch, amount) in simulation.get_pending_owner_unlocks() {
+        println!("-- Owner unlock of {} LSU at epoch {}", amount, epoch.number());
+    }
+    for (epoch, amount) in simulation.get_pending_unstakes() {
+        println!("-- Unstake of {} XRD claimable at epoch {}", amount, epoch.number());
+    }
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use transaction::model::RawNotarizedTransaction;
+
+use crate::gable_simulation::{receipt_error, GableSimulation};
+use crate::state_diff::StateView;
+
+pub struct ReplayOutcome {
+    // line of the transaction in the transactions file
+    pub line: usize,
+    // validation error or engine error, None if committed successfully
+    pub error: Option<String>,
+}
+
+// one hex encoded notarized transaction payload per line, empty lines and lines starting with `#` are skipped
+pub fn load_transactions(path: &Path) -> Vec<(usize, RawNotarizedTransaction)> {
+    let text = std::fs::read_to_string(path).unwrap();
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_line, payload)| !payload.is_empty() && !payload.starts_with('#'))
+        .map(|(line, payload)| {
+            let bytes = hex::decode(payload).unwrap_or_else(|error| panic!("Invalid payload on line {}: {}", line, error));
+            (line, RawNotarizedTransaction(bytes))
+        })
+        .collect()
+}
+
+pub fn replay(simulation: &mut GableSimulation, transactions: &[(usize, RawNotarizedTransaction)]) -> Vec<ReplayOutcome> {
+    let mut outcomes = Vec::new();
+    for (line, transaction) in transactions {
+        let error = match simulation.execute_notarized_transaction("replay", transaction) {
+            Ok(receipt) => receipt_error(&receipt),
+            Err(error) => Some(format!("Validation failed: {}", error)),
+        };
+        match &error {
+            None => println!("-- Transaction on line {} committed", line),
+            Some(error) => println!("-- Transaction on line {} failed: {}", line, error),
+        }
+        outcomes.push(ReplayOutcome { line: *line, error });
+    }
+    outcomes
+}
+
+// differences between replayed state and state of a later snapshot, epoch is not compared
+pub fn compare_with_later_snapshot(replayed: &StateView, later: &StateView) -> Vec<String> {
+    let later = StateView {
+        epoch: replayed.epoch,
+        ..later.clone()
+    };
+    replayed.diff(&later)
+}
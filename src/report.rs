@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use radix_engine_common::prelude::*;
+
+use crate::provenance;
+use crate::scenarios::{Scenario, ScenarioResult};
+
+pub struct ScenarioSummary {
+    pub scenario: Scenario,
+    // first and last day recorded in the csv file, runs resumed from a checkpoint don't start with day 1
+    pub first_day: u64,
+    pub last_day: u64,
+    pub initial_lsu_locked: Decimal,
+    pub final_lsu_locked: Decimal,
+    // snapshot metadata the csv file is stamped with
+    pub snapshot: Vec<String>,
+}
+
+// reads csv files written by scenario runs from the output directory
+pub fn read_summaries(output_dir: &Path) -> Vec<ScenarioSummary> {
+    let mut summaries = Vec::new();
+    for scenario in Scenario::ALL {
+        let path = ScenarioResult::csv_path(scenario, output_dir);
+        let Ok(mut csv) = provenance::csv_reader(&path) else {
+            continue;
+        };
+        let mut days = Vec::new();
+        let mut lsu_locked = Vec::new();
+        for record in csv.records() {
+            let record = record.unwrap();
+            days.push(record[0].parse::<u64>().unwrap());
+            lsu_locked.push(Decimal::from_str(&record[1]).unwrap());
+        }
+        if lsu_locked.is_empty() {
+            continue;
+        }
+        summaries.push(ScenarioSummary {
+            scenario,
+            first_day: days[0],
+            last_day: *days.last().unwrap(),
+            initial_lsu_locked: lsu_locked[0],
+            final_lsu_locked: *lsu_locked.last().unwrap(),
+            snapshot: provenance::read_csv_stamp(&path),
+        });
+    }
+    summaries
+}
+
+pub fn print_report(output_dir: &Path) {
+    let summaries = read_summaries(output_dir);
+    if summaries.is_empty() {
+        println!("No simulation results found in {}", output_dir.display());
+        return;
+    }
+    // results of runs on different snapshots are not comparable
+    let mut snapshots: Vec<&Vec<String>> = Vec::new();
+    for summary in &summaries {
+        if !snapshots.contains(&&summary.snapshot) {
+            snapshots.push(&summary.snapshot);
+        }
+    }
+    if snapshots.len() == 1 {
+        println!("Snapshot:");
+        summaries[0].snapshot.iter().for_each(|line| println!("-- {}", line));
+    } else {
+        println!("Warning: results come from {} different snapshots", snapshots.len());
+        for summary in &summaries {
+            println!("Snapshot of {}:", summary.scenario.name());
+            summary.snapshot.iter().for_each(|line| println!("-- {}", line));
+        }
+    }
+    println!("{:<10} {:>10} {:>10} {:>30} {:>30} {:>30}", "Scenario", "First day", "Last day", "Initial LSU locked", "Final LSU locked", "LSU recovered");
+    for summary in summaries {
+        println!(
+            "{:<10} {:>10} {:>10} {:>30} {:>30} {:>30}",
+            summary.scenario.name(),
+            summary.first_day,
+            summary.last_day,
+            summary.initial_lsu_locked,
+            summary.final_lsu_locked,
+            summary.initial_lsu_locked - summary.final_lsu_locked
+        );
+    }
+}
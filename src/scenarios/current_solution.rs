@@ -0,0 +1,91 @@
+use rand::seq::SliceRandom;
+use radix_engine_interface::macros::dec;
+
+use crate::gable_simulation::GableSimulation;
+use crate::withdrawals::{PartialWithdrawals, WithdrawalMode};
+use crate::scenarios::{advance_day, end_of_day, print_inactive_claims, DayRecord, Scenario, ScenarioConfig, ScenarioResult};
+
+// Recovery plan in cooperation with top three (or `held_back_nfts`) locked LSU holders, their NFTs are withdrawn last.
+pub fn run(simulation: &mut GableSimulation, config: &ScenarioConfig) -> ScenarioResult {
+    let mut rng = config.rng();
+    let mut holders = config.holder_model();
+    let mut partial_withdrawals = PartialWithdrawals::default();
+    let mut result = ScenarioResult::new(Scenario::Current);
+
+    // select top nft's with most lsu to be withdrawn last
+    let held_back_nfts = config.held_back_nfts.unwrap_or(Scenario::Current.default_held_back_nfts());
+    let mut nfts = simulation.get_lsu_claims(false);
+    nfts.sort_by(|_, v1, _, v2| v2.0.cmp(&v1.0));
+    let nfts_with_most_lsu = nfts.into_iter().take(held_back_nfts).collect::<Vec<_>>();
+
+    println!("{} NFTs with most LSU: ", held_back_nfts);
+    for (nft, (lsu, xrd)) in &nfts_with_most_lsu {
+        println!("-- NFT: {:?} with LSU: {} and XRD claim: {}", nft, lsu, xrd);
+    }
+
+    println!("LSU stranded by group deletion workaround: {}", simulation.get_lsu_stranded_by_group_workaround());
+
+    'simulation: for day in config.first_day..=config.days {
+        let rewards = match advance_day(simulation, config, day) {
+            Ok(rewards) => rewards,
+            Err(error) => {
+                result.record_failure(error);
+                break 'simulation;
+            }
+        };
+
+        let owner_liqudity = if config.pool_liquidity_budget { dec!(0) } else { simulation.get_owner_liqudity() };
+        let mut pool_liqudity = simulation.get_pool_liqudity();
+        result.record_day(DayRecord::capture(simulation, day, rewards, pool_liqudity));
+
+        if pool_liqudity > dec!(0) {
+            let transactions = simulation.update_supplier_kvs(); // recalculates user rewards
+            if transactions > 1 {
+                println!("-- User rewards recalculated in {} transactions", transactions);
+            }
+            let mut nfts = simulation.get_lsu_claims(true);
+            for nft_to_remove in &nfts_with_most_lsu {
+                nfts.remove(&nft_to_remove.0);
+            }
+            if config.withdrawal_mode == WithdrawalMode::ProRata {
+                nfts = partial_withdrawals.outstanding(nfts);
+            }
+
+            if holders.is_recovered(&nfts) {
+                println!("No more NFTs to withdraw, all NFTs (except top {}) have been withdrawn", held_back_nfts);
+                print_inactive_claims(&mut holders, &nfts);
+                result.days_to_recovery = Some(day);
+                break 'simulation;
+            }
+
+            let nfts = holders.willing(nfts);
+            if config.withdrawal_mode == WithdrawalMode::ProRata {
+                let lsu = partial_withdrawals.withdraw(simulation, &nfts, pool_liqudity, &mut result.withdrawals);
+                result.record_recovered(lsu);
+            } else {
+                let mut random_nfts = nfts.into_iter().collect::<Vec<_>>();
+                random_nfts.shuffle(&mut rng);
+
+                let mut randomly_selected_nfts = Vec::new();
+                for (nft, (lsu, xrd)) in random_nfts {
+                    if pool_liqudity > dec!(0) && xrd <= pool_liqudity + owner_liqudity {
+                        randomly_selected_nfts.push((nft, lsu, xrd));
+                        pool_liqudity -= xrd;
+                    }
+                }
+
+                for (nft, lsu, xrd) in randomly_selected_nfts {
+                    println!("-- Withdrawing NFT: {:?} with LSU: {} and XRD claim: {}", nft, lsu, xrd);
+                    simulation.acquire_nft(simulation.account, nft.clone());
+                    simulation.withdraw_lsu(nft.clone());
+                    result.record_withdrawal(&nft, lsu, xrd);
+                }
+            }
+        }
+
+        end_of_day(simulation, config, Scenario::Current, day);
+    }
+
+    result.holder_deferrals = holders.deferrals;
+    result
+}
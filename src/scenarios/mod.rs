@@ -0,0 +1,512 @@
+pub mod current_solution;
+pub mod perfect_solution;
+
+use std::path::{Path, PathBuf};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use radix_engine_common::prelude::*;
+use radix_engine_interface::macros::dec;
+
+use crate::checkpoint::Checkpoint;
+use crate::events::EventLog;
+use crate::fees::FeeLedger;
+use crate::gable_simulation::{receipt_error, AuthMode, GableSimulation};
+use crate::holders::{HolderBehaviour, HolderDeferrals, HolderModel};
+use crate::provenance;
+use crate::scheduler::{self, SimulationEvent};
+use crate::unlock_pipeline::UnlockPipeline;
+use crate::withdrawals::{WithdrawalLedger, WithdrawalMode};
+
+#[derive(Clone)]
+pub struct ScenarioConfig {
+    // maximum number of simulated days
+    pub days: u64,
+    // seed for random nft selection, random if not set
+    pub seed: Option<u64>,
+    // directory where csv files are written
+    pub output_dir: PathBuf,
+    // XRD added as validator reward every day
+    pub daily_reward: Decimal,
+    // epochs added every day, 288 epochs is around 24h
+    pub epochs_per_day: u64,
+    // first simulated day, greater than 1 when resuming from a checkpoint
+    pub first_day: u64,
+    // days at the end of which a checkpoint is written to the output directory
+    pub checkpoint_days: Vec<u64>,
+    // recovery plan operations are executed with auth checks when enabled
+    pub auth_mode: AuthMode,
+    pub collect_events: bool,
+    // deposits and flash loans happening every day next to withdrawals
+    pub activity: PoolActivity,
+    // changes of pool parameters made by the admin at the start of given days
+    pub parameter_changes: Vec<ParameterChange>,
+    // NFTs with most LSU withdrawn last, scenario default if not set (3 for current, 1 for perfect)
+    pub held_back_nfts: Option<usize>,
+    pub timing: Timing,
+    pub holders: HolderBehaviour,
+    pub withdrawal_mode: WithdrawalMode,
+    // whole NFTs are selected against pool liquidity only, as pro-rata payouts, instead of pool and owner liquidity
+    pub pool_liquidity_budget: bool,
+}
+
+impl Default for ScenarioConfig {
+    fn default() -> Self {
+        ScenarioConfig {
+            days: 360,
+            seed: None,
+            output_dir: PathBuf::from("."),
+            daily_reward: dec!(8000),
+            epochs_per_day: 288,
+            first_day: 1,
+            checkpoint_days: Vec::new(),
+            auth_mode: AuthMode::Disabled,
+            collect_events: false,
+            activity: PoolActivity::default(),
+            parameter_changes: Vec::new(),
+            held_back_nfts: None,
+            timing: Timing::Daily,
+            holders: HolderBehaviour::default(),
+            withdrawal_mode: WithdrawalMode::WholeNft,
+            pool_liquidity_budget: false,
+        }
+    }
+}
+
+impl ScenarioConfig {
+    // mixed into the seed of the holder model, so its draws don't repeat the NFT shuffle of the same seed
+    const HOLDER_STREAM: u64 = 0x686f6c64657273;
+
+    pub fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+
+    // checkpoints store the simulation, not the state a scenario run keeps itself, so runs depending on it can't use them
+    pub fn checkpoint_support(&self) -> Result<(), String> {
+        if self.withdrawal_mode == WithdrawalMode::ProRata {
+            return Err("Checkpoints don't store shares paid by pro-rata withdrawals, `--withdrawal-mode pro-rata` can't be used with `--checkpoint-days` or `--resume`".to_string());
+        }
+        if self.holders.inactive_share > 0.0 {
+            return Err("Checkpoints don't store which holders were drawn inactive, `--inactive-share` can't be used with `--checkpoint-days` or `--resume`".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn holder_model(&self) -> HolderModel {
+        let rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed ^ Self::HOLDER_STREAM),
+            None => StdRng::from_entropy(),
+        };
+        HolderModel::new(self.holders.clone(), rng)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PoolActivity {
+    // LSU deposited every day by a new supplier
+    pub daily_lsu_deposit: Decimal,
+    // XRD amounts of flash loans taken and repaid every day
+    pub daily_flash_loans: Vec<Decimal>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Timing {
+    // reward is added at the start of the day, matured unlocks and unstakes are processed at its end
+    Daily,
+    // reward arrives in parts spread over the day, unlocks and unstakes are processed at the epoch they mature
+    Events { reward_arrivals_per_day: u64 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Parameter {
+    InterestRate(Decimal),
+    BoxSize(u64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParameterChange {
+    pub day: u64,
+    pub parameter: Parameter,
+}
+
+impl ParameterChange {
+    pub fn apply(&self, simulation: &mut GableSimulation) -> Result<(), String> {
+        match self.parameter {
+            Parameter::InterestRate(interest_rate) => {
+                simulation.update_interest_rate(interest_rate)?;
+                println!("-- Interest rate changed to {}", interest_rate);
+            }
+            Parameter::BoxSize(box_size) => {
+                simulation.update_box_size(box_size)?;
+                println!("-- Box size changed to {}", box_size);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scenario {
+    Current,
+    Perfect,
+}
+
+impl Scenario {
+    pub const ALL: [Scenario; 2] = [Scenario::Current, Scenario::Perfect];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scenario::Current => "current",
+            Scenario::Perfect => "perfect",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Scenario> {
+        Scenario::ALL.into_iter().find(|scenario| scenario.name() == name)
+    }
+
+    // NFTs with most LSU held back until all others are withdrawn
+    pub fn default_held_back_nfts(&self) -> usize {
+        match self {
+            Scenario::Current => 3,
+            Scenario::Perfect => 1,
+        }
+    }
+
+    pub fn run(&self, simulation: &mut GableSimulation, config: &ScenarioConfig) -> ScenarioResult {
+        simulation.auth_mode = config.auth_mode;
+        simulation.events.enabled = config.collect_events;
+        let mut result = match self {
+            Scenario::Current => current_solution::run(simulation, config),
+            Scenario::Perfect => perfect_solution::run(simulation, config),
+        };
+        if config.collect_events {
+            let missing = simulation.withdraw_lsu_transactions_without_vault_withdraw();
+            if !missing.is_empty() {
+                println!("-- withdraw_lsu transactions without LSU vault withdraw event: {:?}", missing);
+            }
+        }
+        result.fees = std::mem::take(&mut simulation.fees);
+        result.events = std::mem::take(&mut simulation.events);
+        // claims redeemable at the end of the run are left behind
+        let epoch = simulation.test_runner.get_current_epoch().number();
+        simulation.unlock_pipeline.report_unclaimed(simulation.day, epoch);
+        result.unlock_pipeline = std::mem::take(&mut simulation.unlock_pipeline);
+        result
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DayRecord {
+    pub day: u64,
+    pub epoch: u64,
+    pub lsu_locked: Decimal,
+    pub rewards: Decimal,
+    pub pool_liquidity: Decimal,
+    // XRD redeemable for one LSU and XRD staked to the validator at the start of the day
+    pub redemption_rate: Decimal,
+    pub validator_stake: Decimal,
+    // LSU withdrawn by suppliers during the day
+    pub recovered_lsu: Decimal,
+    // recovered LSU restaked as validator rewards during the day
+    pub recycled_lsu: Decimal,
+    // change of the redemption rate across the recycling calls of the day
+    pub recycling_rate_change: Decimal,
+}
+
+impl DayRecord {
+    pub fn capture(simulation: &mut GableSimulation, day: u64, rewards: Decimal, pool_liquidity: Decimal) -> Self {
+        DayRecord {
+            day,
+            epoch: simulation.test_runner.get_current_epoch().number(),
+            lsu_locked: simulation.get_lsu_balance(),
+            rewards,
+            pool_liquidity,
+            redemption_rate: simulation.get_lsu_redemption_rate(),
+            validator_stake: simulation.get_validator_stake(),
+            recovered_lsu: dec!(0),
+            recycled_lsu: dec!(0),
+            recycling_rate_change: dec!(0),
+        }
+    }
+
+    pub fn lsu_locked_xrd(&self) -> Decimal {
+        self.lsu_locked * self.redemption_rate
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ScenarioResult {
+    pub scenario: Scenario,
+    pub days: Vec<DayRecord>,
+    // day on which all nfts (except held back ones and those of inactive holders) were withdrawn
+    pub days_to_recovery: Option<u64>,
+    pub fees: FeeLedger,
+    pub events: EventLog,
+    pub unlock_pipeline: UnlockPipeline,
+    pub holder_deferrals: HolderDeferrals,
+    pub withdrawals: WithdrawalLedger,
+    // operation of the plan which failed and stopped the run, e.g. owner stake with auth enabled
+    pub failure: Option<String>,
+}
+
+impl ScenarioResult {
+    pub fn new(scenario: Scenario) -> Self {
+        ScenarioResult {
+            scenario,
+            days: Vec::new(),
+            days_to_recovery: None,
+            fees: FeeLedger::default(),
+            events: EventLog::default(),
+            unlock_pipeline: UnlockPipeline::default(),
+            holder_deferrals: HolderDeferrals::default(),
+            withdrawals: WithdrawalLedger::default(),
+            failure: None,
+        }
+    }
+
+    pub fn record_failure(&mut self, error: String) {
+        println!("-- Recovery plan stopped: {}", error);
+        self.failure = Some(error);
+    }
+
+    pub fn record_day(&mut self, record: DayRecord) {
+        println!("-- Day: {}, epoch {}, LSU locked in contract: {}, new validator rewards: {}, pool liqudity: {}, LSU redemption rate: {}", record.day, record.epoch, record.lsu_locked, record.rewards, record.pool_liquidity, record.redemption_rate);
+        self.days.push(record);
+    }
+
+    // LSU withdrawn on the last recorded day
+    pub fn record_recovered(&mut self, lsu: Decimal) {
+        if let Some(record) = self.days.last_mut() {
+            record.recovered_lsu += lsu;
+        }
+    }
+
+    // whole NFT withdrawn on the last recorded day
+    pub fn record_withdrawal(&mut self, nft: &NonFungibleLocalId, lsu: Decimal, xrd: Decimal) {
+        let day = self.days.last().map(|record| record.day).unwrap_or_default();
+        self.withdrawals.record(day, nft, lsu, xrd, true);
+        self.record_recovered(lsu);
+    }
+
+    // LSU restaked as validator rewards on the last recorded day, with the redemption rate right before and after
+    pub fn record_recycled(&mut self, lsu: Decimal, rate_before: Decimal, rate_after: Decimal) {
+        if let Some(record) = self.days.last_mut() {
+            record.recycled_lsu += lsu;
+            record.recycling_rate_change += rate_after - rate_before;
+        }
+    }
+
+    // recovered LSU valued at the redemption rate of the day it was withdrawn
+    pub fn recovered_xrd(&self) -> Decimal {
+        self.days.iter().fold(dec!(0), |sum, record| sum + record.recovered_lsu * record.redemption_rate)
+    }
+
+    pub fn recycled_xrd(&self) -> Decimal {
+        self.days.iter().fold(dec!(0), |sum, record| sum + record.recycled_lsu * record.redemption_rate)
+    }
+
+    pub fn print_redemption_summary(&self) {
+        let (Some(first), Some(last)) = (self.days.first(), self.days.last()) else {
+            return;
+        };
+        let recovered_lsu = self.days.iter().fold(dec!(0), |sum, record| sum + record.recovered_lsu);
+        let recycled_lsu = self.days.iter().fold(dec!(0), |sum, record| sum + record.recycled_lsu);
+        println!("LSU redemption rate: {} on day {} → {} on day {}, validator stake: {} → {} XRD", first.redemption_rate, first.day, last.redemption_rate, last.day, first.validator_stake, last.validator_stake);
+        // recycling calls are measured right around the call, everything else (rewards, unlocks, deposits) is drift
+        let recycling_rate_change = self.days.iter().fold(dec!(0), |sum, record| sum + record.recycling_rate_change);
+        let total_rate_change = last.redemption_rate - first.redemption_rate;
+        println!("-- Rate change by recycling LSU: {}, by rewards and other operations: {}", recycling_rate_change, total_rate_change - recycling_rate_change);
+        println!("-- LSU locked: {} ({} XRD) → {} ({} XRD)", first.lsu_locked, first.lsu_locked_xrd(), last.lsu_locked, last.lsu_locked_xrd());
+        println!("-- LSU recovered: {} ({} XRD), restaked as rewards: {} ({} XRD)", recovered_lsu, self.recovered_xrd(), recycled_lsu, self.recycled_xrd());
+        if !recovered_lsu.is_zero() {
+            // the same LSU valued at the final rate, positive if LSU gained value while it was recovered
+            println!("-- Recovered LSU at the final rate: {} XRD, difference: {} XRD", recovered_lsu * last.redemption_rate, recovered_lsu * last.redemption_rate - self.recovered_xrd());
+        }
+    }
+
+    pub fn final_lsu_locked(&self) -> Decimal {
+        self.days.last().map(|record| record.lsu_locked).unwrap_or(dec!(0))
+    }
+
+    pub fn csv_path(scenario: Scenario, output_dir: &Path) -> PathBuf {
+        output_dir.join(format!("{}_simulation.csv", scenario.name()))
+    }
+
+    pub fn write_csv(&self, output_dir: &Path) {
+        let mut csv = provenance::csv_writer(&Self::csv_path(self.scenario, output_dir));
+        csv.write_record(["Day", "LSU locked", "Redemption rate", "LSU locked (XRD)", "Validator stake", "Recovered LSU", "Recycled LSU", "Recycling rate change"]).unwrap();
+        for record in &self.days {
+            csv.write_record(&[
+                record.day.to_string(),
+                record.lsu_locked.to_string(),
+                record.redemption_rate.to_string(),
+                record.lsu_locked_xrd().to_string(),
+                record.validator_stake.to_string(),
+                record.recovered_lsu.to_string(),
+                record.recycled_lsu.to_string(),
+                record.recycling_rate_change.to_string(),
+            ]).unwrap();
+        }
+        csv.flush().unwrap();
+        self.fees.write_csv(&output_dir.join(format!("{}_fees.csv", self.scenario.name())));
+        self.fees.write_per_day_csv(&output_dir.join(format!("{}_fees_per_day.csv", self.scenario.name())));
+        self.unlock_pipeline.write_csv(&output_dir.join(format!("{}_unlocks.csv", self.scenario.name())));
+        self.unlock_pipeline.write_claims_csv(&output_dir.join(format!("{}_claims.csv", self.scenario.name())));
+        self.withdrawals.write_csv(&output_dir.join(format!("{}_withdrawals.csv", self.scenario.name())));
+        if self.events.enabled {
+            self.events.write_csv(&output_dir.join(format!("{}_events.csv", self.scenario.name())));
+        }
+    }
+}
+
+// adds reward from validator, moves epoch (time) by one day and processes matured unlocks and unstakes, returns claimed XRD,
+// fails if a parameter change of the day fails
+pub fn advance_day(simulation: &mut GableSimulation, config: &ScenarioConfig, day: u64) -> Result<Decimal, String> {
+    simulation.day = day;
+    for change in config.parameter_changes.iter().filter(|change| change.day == day) {
+        change.apply(simulation)?;
+    }
+    if let Timing::Events { reward_arrivals_per_day } = config.timing {
+        let rewards = advance_day_by_events(simulation, config, reward_arrivals_per_day.max(1));
+        apply_pool_activity(simulation, &config.activity);
+        return Ok(rewards);
+    }
+    simulation.add_validator_reward(config.daily_reward);
+    simulation.add_epoch(config.epochs_per_day);
+
+    // a single call finishes all matured unlocks and claims all claimable unstakes
+    let epoch = simulation.test_runner.get_current_epoch();
+    if simulation.get_pending_owner_unlocks().keys().any(|unlock_epoch| *unlock_epoch <= epoch) {
+        simulation.finish_unlock_and_unstake();
+    }
+    let rewards = simulation.get_pending_unstakes()
+        .iter()
+        .filter(|unlock| unlock.0 <= epoch)
+        .fold(dec!(0), |sum, (_epoch, reward)| sum + *reward);
+    if rewards > dec!(0) {
+        simulation.claim_xrd();
+    }
+    apply_pool_activity(simulation, &config.activity);
+    Ok(rewards)
+}
+
+// schedules reward arrivals of the day and processes all events until the end of the day, returns claimed XRD
+fn advance_day_by_events(simulation: &mut GableSimulation, config: &ScenarioConfig, reward_arrivals: u64) -> Decimal {
+    let day_start = simulation.test_runner.get_current_epoch().number();
+    let reward = config.daily_reward / reward_arrivals;
+    for arrival in 0..reward_arrivals {
+        let epoch = day_start + config.epochs_per_day * arrival / reward_arrivals;
+        simulation.scheduler.schedule(epoch, SimulationEvent::RewardArrival(reward));
+    }
+    let processed = scheduler::run_until(simulation, day_start + config.epochs_per_day);
+    processed.claimed_xrd
+}
+
+pub fn apply_pool_activity(simulation: &mut GableSimulation, activity: &PoolActivity) {
+    if activity.daily_lsu_deposit > dec!(0) {
+        simulation.acquire_lsu(simulation.account, activity.daily_lsu_deposit);
+        match simulation.deposit_lsu(simulation.account, activity.daily_lsu_deposit) {
+            Ok(nft) => println!("-- New supplier deposited {} LSU and received NFT: {:?}", activity.daily_lsu_deposit, nft),
+            Err(error) => println!("-- Deposit of {} LSU failed: {}", activity.daily_lsu_deposit, error),
+        }
+    }
+    for amount in &activity.daily_flash_loans {
+        let interest = simulation.get_flash_loan_interest(*amount);
+        let receipt = simulation.flash_loan(simulation.account, *amount);
+        match receipt_error(&receipt) {
+            None => println!("-- Flash loan of {} XRD repaid with {} XRD interest", amount, interest),
+            Some(error) => println!("-- Flash loan of {} XRD failed: {}", amount, error),
+        }
+    }
+}
+
+pub fn print_inactive_claims(holders: &mut HolderModel, nfts: &IndexMap<NonFungibleLocalId, (Decimal, Decimal)>) {
+    let (count, lsu, xrd) = holders.inactive_claims(nfts);
+    if count > 0 {
+        println!("-- {} NFTs of inactive holders with {} LSU and XRD claim {} are left in the pool", count, lsu, xrd);
+    }
+}
+
+pub fn end_of_day(simulation: &mut GableSimulation, config: &ScenarioConfig, scenario: Scenario, day: u64) {
+    let state = simulation.unlock_pipeline_state();
+    let redemption_rate = simulation.get_lsu_redemption_rate();
+    simulation.unlock_pipeline.record_day(day, &state, redemption_rate, config.epochs_per_day);
+    if config.checkpoint_days.contains(&day) {
+        let path = config.output_dir.join(Checkpoint::file_name(scenario.name(), day));
+        Checkpoint::capture(simulation, scenario.name(), day).save(&path);
+        println!("-- Checkpoint saved to {}", path.display());
+    }
+}
+
+pub struct Comparison {
+    pub results: Vec<ScenarioResult>,
+}
+
+impl Comparison {
+    pub fn print(&self) {
+        println!("{:<10} {:>18} {:>30} {:>30} {:>30}", "Scenario", "Days to recovery", "Final LSU locked", "Recovered (XRD)", "Operator fees (XRD)");
+        for result in &self.results {
+            let days = match (&result.failure, result.days_to_recovery) {
+                (Some(_error), _) => "failed".to_string(),
+                (None, days) => days.map(|days| days.to_string()).unwrap_or("-".to_string()),
+            };
+            println!("{:<10} {:>18} {:>30} {:>30} {:>30}", result.scenario.name(), days, result.final_lsu_locked(), result.recovered_xrd(), result.fees.operator_cost());
+        }
+    }
+
+    pub fn write_csv(&self, output_dir: &Path) {
+        let mut csv = provenance::csv_writer(&output_dir.join("compare.csv"));
+        let mut header = vec!["Day".to_string()];
+        header.extend(self.results.iter().map(|result| result.scenario.name().to_string()));
+        csv.write_record(&header).unwrap();
+        // days as recorded, runs resumed from a checkpoint don't start with day 1
+        let days: BTreeSet<u64> = self.results.iter().flat_map(|result| result.days.iter().map(|record| record.day)).collect();
+        for day in days {
+            let mut row = vec![day.to_string()];
+            for result in &self.results {
+                row.push(result.days.iter().find(|record| record.day == day).map(|record| record.lsu_locked.to_string()).unwrap_or_default());
+            }
+            csv.write_record(&row).unwrap();
+        }
+        csv.flush().unwrap();
+    }
+}
+
+pub struct MonteCarloSummary {
+    pub scenario: Scenario,
+    // (seed, days to recovery)
+    pub runs: Vec<(u64, Option<u64>)>,
+}
+
+impl MonteCarloSummary {
+    pub fn print(&self) {
+        for (seed, days) in &self.runs {
+            match days {
+                Some(days) => println!("-- Seed: {}, days to recovery: {}", seed, days),
+                None => println!("-- Seed: {}, not recovered", seed),
+            }
+        }
+        let recovered = self.runs.iter().filter_map(|(_seed, days)| *days).collect::<Vec<_>>();
+        if recovered.is_empty() {
+            println!("Scenario {}: none of {} runs recovered", self.scenario.name(), self.runs.len());
+            return;
+        }
+        let min = recovered.iter().min().unwrap();
+        let max = recovered.iter().max().unwrap();
+        let mean = recovered.iter().sum::<u64>() as f64 / recovered.len() as f64;
+        println!("Scenario {}: {} of {} runs recovered, days min: {}, mean: {:.1}, max: {}", self.scenario.name(), recovered.len(), self.runs.len(), min, mean, max);
+    }
+
+    pub fn write_csv(&self, output_dir: &Path) {
+        let mut csv = provenance::csv_writer(&output_dir.join(format!("{}_montecarlo.csv", self.scenario.name())));
+        csv.write_record(["Seed", "Days to recovery"]).unwrap();
+        for (seed, days) in &self.runs {
+            csv.write_record(&[seed.to_string(), days.map(|days| days.to_string()).unwrap_or_default()]).unwrap();
+        }
+        csv.flush().unwrap();
+    }
+}
@@ -0,0 +1,145 @@
+use radix_engine_interface::macros::dec;
+
+use crate::gable_simulation::GableSimulation;
+use crate::withdrawals::{PartialWithdrawals, WithdrawalMode};
+use crate::scenarios::{advance_day, end_of_day, print_inactive_claims, DayRecord, Scenario, ScenarioConfig, ScenarioResult};
+
+// Simplified "perfect solution": owner liquidity and recovered user LSU are used as validator rewards.
+pub fn run(simulation: &mut GableSimulation, config: &ScenarioConfig) -> ScenarioResult {
+    let mut result = ScenarioResult::new(Scenario::Perfect);
+    let mut holders = config.holder_model();
+    let mut partial_withdrawals = PartialWithdrawals::default();
+
+    // select nft (or `held_back_nfts` nfts) with most lsu to be withdrawn last
+    let held_back_nfts = config.held_back_nfts.unwrap_or(Scenario::Perfect.default_held_back_nfts());
+    let mut nfts = simulation.get_lsu_claims(false);
+    nfts.sort_by(|_, v1, _, v2| v2.0.cmp(&v1.0));
+    let nfts_with_most_lsu = nfts.into_iter().take(held_back_nfts).collect::<Vec<_>>();
+
+    for (nft, (lsu, xrd)) in &nfts_with_most_lsu {
+        println!("NFT with most LSU: {:}, LSU: {}, XRD claim: {}", nft, lsu, xrd);
+    }
+
+    println!("LSU stranded by group deletion workaround: {}", simulation.get_lsu_stranded_by_group_workaround());
+
+    // owner liquidity is withdrawn at the end of step 1, so step 1 is skipped when resuming after it
+    let mut day = config.first_day - 1;
+    if day == 0 || simulation.get_owner_liqudity() > dec!(0) {
+        println!("STEP 1: Recover owner liquidity and use it as validator reward");
+        let owner_liqudity = simulation.get_owner_liqudity();
+        let pool_liqudity = simulation.get_xrd_balance();
+        let owner_xrd_balance = simulation.get_owner_xrd_balance();
+        println!("-- Owner liquidity: {}, Pool liquidity: {}, Owner account XRD balance: {}", owner_liqudity, pool_liqudity, owner_xrd_balance);
+        let needed_xrd = pool_liqudity - owner_liqudity;
+        println!("-- We need {} XRD rewards to recover owner liquidity", needed_xrd);
+
+        while day < config.days.min(100) {
+            day += 1;
+            let rewards = match advance_day(simulation, config, day) {
+                Ok(rewards) => rewards,
+                Err(error) => {
+                    result.record_failure(error);
+                    result.holder_deferrals = holders.deferrals;
+                    return result;
+                }
+            };
+            let owner_liqudity = simulation.get_owner_liqudity();
+            let pool_liqudity = simulation.get_xrd_balance();
+            result.record_day(DayRecord::capture(simulation, day, rewards, pool_liqudity));
+            if pool_liqudity > owner_liqudity {
+                println!("-- Pool liquidity ({}) is higher than owner liquidity ({}), we can recover owner liquidity", owner_liqudity, pool_liqudity);
+                simulation.owner_withdraw_xrd(owner_liqudity);
+                break;
+            }
+            end_of_day(simulation, config, Scenario::Perfect, day);
+        }
+
+        let owner_xrd_balance = simulation.get_owner_xrd_balance();
+        let owner_lsu_balance = simulation.get_owner_lsu_balance();
+        println!("-- Gable Owner account XRD balance: {}, LSU balance: {}", owner_xrd_balance, owner_lsu_balance);
+        let reward_to_add = owner_xrd_balance - 100;
+        println!("-- Adding {} XRD rewards to the pool by using Gable Owner account XRD", reward_to_add);
+        if let Err(error) = simulation.stake_xrd_as_owner_and_start_unlock(simulation.gable_owner_account, reward_to_add) {
+            result.record_failure(error);
+            result.holder_deferrals = holders.deferrals;
+            return result;
+        }
+        end_of_day(simulation, config, Scenario::Perfect, day);
+    }
+
+    println!("STEP 2: Recover users LSU and use them as validator reward till");
+
+    'simulation: while day < config.days {
+        day += 1;
+
+        let rewards = match advance_day(simulation, config, day) {
+            Ok(rewards) => rewards,
+            Err(error) => {
+                result.record_failure(error);
+                break 'simulation;
+            }
+        };
+        let owner_liqudity = if config.pool_liquidity_budget { dec!(0) } else { simulation.get_owner_liqudity() };
+        let mut pool_liqudity = simulation.get_pool_liqudity();
+        result.record_day(DayRecord::capture(simulation, day, rewards, pool_liqudity));
+
+        if pool_liqudity > dec!(0) {
+            let transactions = simulation.update_supplier_kvs(); // recalculates user rewards
+            if transactions > 1 {
+                println!("-- User rewards recalculated in {} transactions", transactions);
+            }
+            let mut nfts = simulation.get_lsu_claims(true);
+            for nft_to_remove in &nfts_with_most_lsu {
+                nfts.remove(&nft_to_remove.0);
+            }
+            if config.withdrawal_mode == WithdrawalMode::ProRata {
+                nfts = partial_withdrawals.outstanding(nfts);
+            }
+            nfts.sort_by(|_, v1, _, v2| v2.0.cmp(&v1.0));
+
+            if holders.is_recovered(&nfts) {
+                println!("No more NFTs to withdraw, all NFTs (except {} with the most LSU) have been withdrawn", held_back_nfts);
+                print_inactive_claims(&mut holders, &nfts);
+                result.days_to_recovery = Some(day);
+                break 'simulation;
+            }
+
+            let nfts = holders.willing(nfts);
+            let mut recovered_lsu = dec!(0);
+            if config.withdrawal_mode == WithdrawalMode::ProRata {
+                recovered_lsu = partial_withdrawals.withdraw(simulation, &nfts, pool_liqudity, &mut result.withdrawals);
+                result.record_recovered(recovered_lsu);
+            } else {
+                let mut randomly_selected_nfts = Vec::new();
+                for (nft, (lsu, xrd)) in nfts {
+                    if pool_liqudity > dec!(0) && xrd <= pool_liqudity + owner_liqudity {
+                        randomly_selected_nfts.push((nft, lsu, xrd));
+                        pool_liqudity -= xrd;
+                    }
+                }
+
+                for (nft, lsu, xrd) in randomly_selected_nfts {
+                    println!("-- Withdrawing NFT: {:?} with LSU: {} and XRD claim: {}", nft, lsu, xrd);
+                    simulation.acquire_nft(simulation.account, nft.clone());
+                    simulation.withdraw_lsu(nft.clone());
+                    result.record_withdrawal(&nft, lsu, xrd);
+                    recovered_lsu += lsu;
+                }
+            }
+            if recovered_lsu > dec!(0) {
+                println!("-- Using recovered {} LSU from NFTs as new validator rewards", recovered_lsu);
+                let rate_before = simulation.get_lsu_redemption_rate();
+                if let Err(error) = simulation.stake_lsu_as_owner_and_start_unlock(simulation.account, recovered_lsu) {
+                    result.record_failure(error);
+                    break 'simulation;
+                }
+                result.record_recycled(recovered_lsu, rate_before, simulation.get_lsu_redemption_rate());
+            }
+        }
+
+        end_of_day(simulation, config, Scenario::Perfect, day);
+    }
+
+    result.holder_deferrals = holders.deferrals;
+    result
+}
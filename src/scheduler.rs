@@ -0,0 +1,112 @@
+use radix_engine_common::prelude::*;
+use radix_engine_derive::ScryptoSbor;
+use radix_engine_interface::macros::dec;
+
+use crate::gable_simulation::GableSimulation;
+
+#[derive(Clone, Debug, PartialEq, Eq, ScryptoSbor)]
+pub enum SimulationEvent {
+    // validator reward staked by the owner and put into owner unlock
+    RewardArrival(Decimal),
+    // pending owner unlock can be finished and unstaked
+    OwnerUnlockMatures,
+    // unstake claim nft in `unstaking_nft_vault` can be claimed
+    ClaimRedeemable,
+}
+
+// Timed events processed in epoch order, events of the same epoch in order of scheduling.
+#[derive(Clone, Debug, Default, ScryptoSbor)]
+pub struct Scheduler {
+    queue: BTreeMap<(u64, u64), SimulationEvent>,
+    next_sequence: u64,
+}
+
+impl Scheduler {
+    pub fn schedule(&mut self, epoch: u64, event: SimulationEvent) {
+        self.queue.insert((epoch, self.next_sequence), event);
+        self.next_sequence += 1;
+    }
+
+    pub fn is_scheduled(&self, epoch: u64, event: &SimulationEvent) -> bool {
+        self.queue.range((epoch, 0)..(epoch + 1, 0)).any(|(_key, scheduled)| scheduled == event)
+    }
+
+    // removes the earliest event scheduled at or before `epoch`
+    pub fn pop_until(&mut self, epoch: u64) -> Option<(u64, SimulationEvent)> {
+        let key = *self.queue.keys().next()?;
+        if key.0 > epoch {
+            return None;
+        }
+        self.queue.remove(&key).map(|event| (key.0, event))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ProcessedEvents {
+    pub events: usize,
+    pub rewards: Decimal,
+    pub claimed_xrd: Decimal,
+}
+
+// schedules maturity of pending owner unlocks and unstake claims which are not scheduled yet
+pub fn schedule_pipeline(simulation: &mut GableSimulation) {
+    let state = simulation.unlock_pipeline_state();
+    for maturity_epoch in state.owner_unlocks.keys() {
+        if !simulation.scheduler.is_scheduled(*maturity_epoch, &SimulationEvent::OwnerUnlockMatures) {
+            simulation.scheduler.schedule(*maturity_epoch, SimulationEvent::OwnerUnlockMatures);
+        }
+    }
+    for (claim_epoch, _xrd) in state.unstake_claims.values() {
+        if !simulation.scheduler.is_scheduled(*claim_epoch, &SimulationEvent::ClaimRedeemable) {
+            simulation.scheduler.schedule(*claim_epoch, SimulationEvent::ClaimRedeemable);
+        }
+    }
+}
+
+// Processes events scheduled up to `epoch`, moving the ledger epoch to each of them, and ends at `epoch`.
+// Unlocks and claims created by processed events are scheduled and processed too if they mature before `epoch`.
+pub fn run_until(simulation: &mut GableSimulation, epoch: u64) -> ProcessedEvents {
+    let mut processed = ProcessedEvents::default();
+    schedule_pipeline(simulation);
+    while let Some((event_epoch, event)) = simulation.scheduler.pop_until(epoch) {
+        move_to_epoch(simulation, event_epoch);
+        processed.events += 1;
+        match event {
+            SimulationEvent::RewardArrival(amount) => {
+                simulation.add_validator_reward(amount);
+                processed.rewards += amount;
+                schedule_pipeline(simulation);
+            }
+            SimulationEvent::OwnerUnlockMatures => {
+                // several unlocks can mature at the same epoch, the first event finishes all of them
+                let current_epoch = simulation.test_runner.get_current_epoch();
+                if simulation.get_pending_owner_unlocks().keys().any(|unlock_epoch| *unlock_epoch <= current_epoch) {
+                    simulation.finish_unlock_and_unstake();
+                    schedule_pipeline(simulation);
+                }
+            }
+            SimulationEvent::ClaimRedeemable => {
+                let current_epoch = simulation.test_runner.get_current_epoch();
+                let claimable = simulation
+                    .get_pending_unstakes()
+                    .iter()
+                    .filter(|(claim_epoch, _xrd)| *claim_epoch <= current_epoch)
+                    .fold(dec!(0), |sum, (_epoch, xrd)| sum + *xrd);
+                if claimable > dec!(0) {
+                    simulation.claim_xrd();
+                    processed.claimed_xrd += claimable;
+                }
+            }
+        }
+    }
+    move_to_epoch(simulation, epoch);
+    processed
+}
+
+// events scheduled in the past are processed at the current epoch
+fn move_to_epoch(simulation: &mut GableSimulation, epoch: u64) {
+    let current_epoch = simulation.test_runner.get_current_epoch().number();
+    if epoch > current_epoch {
+        simulation.add_epoch(epoch - current_epoch);
+    }
+}
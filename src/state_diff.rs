@@ -0,0 +1,155 @@
+use radix_engine_common::prelude::*;
+
+use crate::gable_simulation::GableSimulation;
+
+// Substates reachable from the Gable component and the validator, decoded into named fields.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateView {
+    pub epoch: u64,
+    pub gable_fields: IndexMap<String, String>,
+    // vault balances by field name of the vault in `Flashloanpool`
+    pub vaults: IndexMap<String, Decimal>,
+    pub supplier_aggregate_im: IndexMap<u64, Vec<Decimal>>,
+    pub supplier_partitioned_kvs: IndexMap<u64, IndexMap<NonFungibleLocalId, Vec<Decimal>>>,
+    pub nft_vec: Vec<NonFungibleLocalId>,
+    pub validator_fields: IndexMap<String, String>,
+    pub pending_owner_unlocks: BTreeMap<Epoch, Decimal>,
+}
+
+impl StateView {
+    pub fn capture(simulation: &mut GableSimulation) -> Self {
+        let gable_state = simulation.gable_state();
+        let validator_state = simulation.validator_state();
+
+        let mut gable_fields = IndexMap::new();
+        gable_fields.insert("owner_liquidity".to_string(), gable_state.owner_liquidity.to_string());
+        gable_fields.insert("rewards_liquidity".to_string(), gable_state.rewards_liquidity.to_string());
+        gable_fields.insert("interest_rate".to_string(), gable_state.interest_rate.to_string());
+        gable_fields.insert("box_size".to_string(), gable_state.box_size.to_string());
+        gable_fields.insert("pool_nft_nr".to_string(), gable_state.pool_nft_nr.to_string());
+
+        let mut vaults = IndexMap::new();
+        let gable_vaults = [
+            ("liquidity_pool_vault", &gable_state.liquidity_pool_vault),
+            ("lsu_vault", &gable_state.lsu_vault),
+            ("validator_owner_vault", &gable_state.validator_owner_vault),
+            ("unstaking_lsu_vault", &gable_state.unstaking_lsu_vault),
+            ("unstaking_nft_vault", &gable_state.unstaking_nft_vault),
+        ];
+        for (name, vault) in gable_vaults {
+            let balance = simulation.test_runner.inspect_vault_balance(*vault.0.as_node_id()).unwrap_or_default();
+            vaults.insert(name.to_string(), balance);
+        }
+        let stake_vault_balance = simulation.test_runner.inspect_vault_balance(validator_state.stake_xrd_vault_id.0).unwrap_or_default();
+        vaults.insert("validator.stake_xrd_vault".to_string(), stake_vault_balance);
+
+        let mut validator_fields = IndexMap::new();
+        validator_fields.insert("is_registered".to_string(), validator_state.is_registered.to_string());
+        validator_fields.insert("accepts_delegated_stake".to_string(), validator_state.accepts_delegated_stake.to_string());
+        validator_fields.insert("validator_fee_factor".to_string(), validator_state.validator_fee_factor.to_string());
+        validator_fields.insert("already_unlocked_owner_stake_unit_amount".to_string(), validator_state.already_unlocked_owner_stake_unit_amount.to_string());
+
+        StateView {
+            epoch: simulation.test_runner.get_current_epoch().number(),
+            gable_fields,
+            vaults,
+            supplier_aggregate_im: gable_state.supplier_aggregate_im,
+            supplier_partitioned_kvs: simulation.get_supplier_partitioned_kvs(),
+            nft_vec: gable_state.nft_vec,
+            validator_fields,
+            pending_owner_unlocks: validator_state.pending_owner_stake_unit_withdrawals,
+        }
+    }
+
+    // human readable list of changes from `self` to `other`
+    pub fn diff(&self, other: &StateView) -> Vec<String> {
+        let mut changes = Vec::new();
+        if self.epoch != other.epoch {
+            changes.push(format!("epoch {} → {}", self.epoch, other.epoch));
+        }
+        diff_fields(&mut changes, "", &self.gable_fields, &other.gable_fields);
+        for (name, balance) in &self.vaults {
+            let other_balance = other.vaults.get(name).cloned().unwrap_or_default();
+            if *balance != other_balance {
+                changes.push(format!("`{}` balance {} → {}", name, balance, other_balance));
+            }
+        }
+        for (group, amounts) in &self.supplier_aggregate_im {
+            match other.supplier_aggregate_im.get(group) {
+                None => changes.push(format!("`supplier_aggregate_im` group {} removed", group)),
+                Some(other_amounts) if other_amounts != amounts => changes.push(format!("`supplier_aggregate_im` group {} {} → {}", group, format_amounts(amounts), format_amounts(other_amounts))),
+                Some(_) => {}
+            }
+        }
+        for (group, amounts) in &other.supplier_aggregate_im {
+            if !self.supplier_aggregate_im.contains_key(group) {
+                changes.push(format!("`supplier_aggregate_im` group {} added {}", group, format_amounts(amounts)));
+            }
+        }
+        let empty = IndexMap::new();
+        let groups = self.supplier_partitioned_kvs.keys().chain(other.supplier_partitioned_kvs.keys()).cloned().collect::<IndexSet<u64>>();
+        for group in groups {
+            let entries = self.supplier_partitioned_kvs.get(&group).unwrap_or(&empty);
+            let other_entries = other.supplier_partitioned_kvs.get(&group).unwrap_or(&empty);
+            for (nft, amounts) in entries {
+                match other_entries.get(nft) {
+                    None => changes.push(format!("group {} entry {} removed", group, nft)),
+                    Some(other_amounts) if other_amounts != amounts => changes.push(format!("group {} entry {} {} → {}", group, nft, format_amounts(amounts), format_amounts(other_amounts))),
+                    Some(_) => {}
+                }
+            }
+            for (nft, amounts) in other_entries {
+                if !entries.contains_key(nft) {
+                    changes.push(format!("group {} entry {} added {}", group, nft, format_amounts(amounts)));
+                }
+            }
+        }
+        for nft in &self.nft_vec {
+            if !other.nft_vec.contains(nft) {
+                changes.push(format!("`nft_vec` unstake claim {} removed", nft));
+            }
+        }
+        for nft in &other.nft_vec {
+            if !self.nft_vec.contains(nft) {
+                changes.push(format!("`nft_vec` unstake claim {} added", nft));
+            }
+        }
+        diff_fields(&mut changes, "validator.", &self.validator_fields, &other.validator_fields);
+        for (epoch, amount) in &self.pending_owner_unlocks {
+            match other.pending_owner_unlocks.get(epoch) {
+                None => changes.push(format!("validator pending owner unlock at epoch {} of {} removed", epoch.number(), amount)),
+                Some(other_amount) if other_amount != amount => changes.push(format!("validator pending owner unlock at epoch {} {} → {}", epoch.number(), amount, other_amount)),
+                Some(_) => {}
+            }
+        }
+        for (epoch, amount) in &other.pending_owner_unlocks {
+            if !self.pending_owner_unlocks.contains_key(epoch) {
+                changes.push(format!("validator pending owner unlock at epoch {} of {} added", epoch.number(), amount));
+            }
+        }
+        changes
+    }
+}
+
+fn diff_fields(changes: &mut Vec<String>, prefix: &str, fields: &IndexMap<String, String>, other_fields: &IndexMap<String, String>) {
+    for (name, value) in fields {
+        let other_value = other_fields.get(name).cloned().unwrap_or_default();
+        if *value != other_value {
+            changes.push(format!("`{}{}` {} → {}", prefix, name, value, other_value));
+        }
+    }
+}
+
+fn format_amounts(amounts: &[Decimal]) -> String {
+    format!("[{}]", amounts.iter().map(|amount| amount.to_string()).collect::<Vec<_>>().join(", "))
+}
+
+pub fn print_diff(before: &StateView, after: &StateView) {
+    let changes = before.diff(after);
+    if changes.is_empty() {
+        println!("-- No changes");
+    }
+    for change in changes {
+        println!("-- {}", change);
+    }
+}
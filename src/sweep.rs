@@ -0,0 +1,194 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use radix_engine_common::prelude::*;
+
+use crate::checkpoint::Checkpoint;
+use crate::execute_within_environment;
+use crate::provenance;
+use crate::scenarios::{Scenario, ScenarioConfig};
+
+// Values of every swept parameter, the grid is their cartesian product.
+#[derive(Clone, Debug)]
+pub struct SweepRanges {
+    pub daily_rewards: Vec<Decimal>,
+    pub epochs_per_day: Vec<u64>,
+    pub held_back_nfts: Vec<usize>,
+    pub daily_lsu_deposits: Vec<Decimal>,
+}
+
+impl SweepRanges {
+    pub fn points(&self) -> Vec<SweepPoint> {
+        let mut points = Vec::new();
+        for held_back_nfts in &self.held_back_nfts {
+            for epochs_per_day in &self.epochs_per_day {
+                for daily_lsu_deposit in &self.daily_lsu_deposits {
+                    for daily_reward in &self.daily_rewards {
+                        points.push(SweepPoint {
+                            daily_reward: *daily_reward,
+                            epochs_per_day: *epochs_per_day,
+                            held_back_nfts: *held_back_nfts,
+                            daily_lsu_deposit: *daily_lsu_deposit,
+                        });
+                    }
+                }
+            }
+        }
+        points
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SweepPoint {
+    pub daily_reward: Decimal,
+    pub epochs_per_day: u64,
+    pub held_back_nfts: usize,
+    pub daily_lsu_deposit: Decimal,
+}
+
+impl SweepPoint {
+    pub fn config(&self, base: &ScenarioConfig) -> ScenarioConfig {
+        let mut config = base.clone();
+        config.daily_reward = self.daily_reward;
+        config.epochs_per_day = self.epochs_per_day;
+        config.held_back_nfts = Some(self.held_back_nfts);
+        config.activity.daily_lsu_deposit = self.daily_lsu_deposit;
+        config.checkpoint_days = Vec::new();
+        config
+    }
+
+    // parameters other than the daily reward, used as heatmap row
+    fn row_label(&self) -> String {
+        format!("held back {}, {} epochs/day, {} LSU/day", self.held_back_nfts, self.epochs_per_day, self.daily_lsu_deposit)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SweepOutcome {
+    pub point: SweepPoint,
+    pub days_to_recovery: Option<u64>,
+    pub final_lsu_locked: Decimal,
+}
+
+pub struct SweepResult {
+    pub scenario: Scenario,
+    pub outcomes: Vec<SweepOutcome>,
+}
+
+// Runs every point of the grid on its own overlay of the snapshot (or the checkpoint), `jobs` points at a time.
+pub fn run(scenario: Scenario, base: &ScenarioConfig, ranges: &SweepRanges, jobs: usize, checkpoint: Option<&Checkpoint>) -> SweepResult {
+    let points = ranges.points();
+    let next_point = AtomicUsize::new(0);
+    let outcomes = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.max(1) {
+            scope.spawn(|| loop {
+                let index = next_point.fetch_add(1, Ordering::SeqCst);
+                let Some(point) = points.get(index) else {
+                    break;
+                };
+                let config = point.config(base);
+                let result = match checkpoint {
+                    Some(checkpoint) => scenario.run(&mut checkpoint.resume(), &config),
+                    None => execute_within_environment(|mut simulation| scenario.run(&mut simulation, &config)),
+                };
+                let outcome = SweepOutcome {
+                    point: point.clone(),
+                    days_to_recovery: result.days_to_recovery,
+                    final_lsu_locked: result.final_lsu_locked(),
+                };
+                outcomes.lock().unwrap().push((index, outcome));
+            });
+        }
+    });
+    let mut outcomes = outcomes.into_inner().unwrap();
+    outcomes.sort_by_key(|(index, _outcome)| *index);
+    SweepResult {
+        scenario,
+        outcomes: outcomes.into_iter().map(|(_index, outcome)| outcome).collect(),
+    }
+}
+
+impl SweepResult {
+    pub fn print(&self) {
+        println!("{:>16} {:>14} {:>10} {:>16} {:>18} {:>30}", "Daily reward", "Epochs/day", "Held back", "LSU deposit/day", "Days to recovery", "Final LSU locked");
+        for outcome in &self.outcomes {
+            let days = outcome.days_to_recovery.map(|days| days.to_string()).unwrap_or("-".to_string());
+            println!(
+                "{:>16} {:>14} {:>10} {:>16} {:>18} {:>30}",
+                outcome.point.daily_reward,
+                outcome.point.epochs_per_day,
+                outcome.point.held_back_nfts,
+                outcome.point.daily_lsu_deposit,
+                days,
+                outcome.final_lsu_locked
+            );
+        }
+    }
+
+    pub fn write_csv(&self, output_dir: &Path) {
+        let mut csv = provenance::csv_writer(&output_dir.join(format!("{}_sweep.csv", self.scenario.name())));
+        csv.write_record(["Daily reward", "Epochs per day", "Held back NFTs", "Daily LSU deposit", "Days to recovery", "Final LSU locked"]).unwrap();
+        for outcome in &self.outcomes {
+            csv.write_record(&[
+                outcome.point.daily_reward.to_string(),
+                outcome.point.epochs_per_day.to_string(),
+                outcome.point.held_back_nfts.to_string(),
+                outcome.point.daily_lsu_deposit.to_string(),
+                outcome.days_to_recovery.map(|days| days.to_string()).unwrap_or_default(),
+                outcome.final_lsu_locked.to_string(),
+            ]).unwrap();
+        }
+        csv.flush().unwrap();
+    }
+
+    // heatmaps of days to recovery and final LSU locked, daily reward in columns and other parameters in rows
+    pub fn write_heatmaps(&self, output_dir: &Path) {
+        let days = self.heatmap("Days to recovery", |outcome| outcome.days_to_recovery.map(|days| days as f64), |value| format!("{}", value));
+        std::fs::write(output_dir.join(format!("{}_sweep_days.svg", self.scenario.name())), days).unwrap();
+        let lsu = self.heatmap("Final LSU locked", |outcome| outcome.final_lsu_locked.to_string().parse::<f64>().ok(), |value| format!("{:.0}", value));
+        std::fs::write(output_dir.join(format!("{}_sweep_lsu.svg", self.scenario.name())), lsu).unwrap();
+    }
+
+    fn heatmap(&self, title: &str, value: impl Fn(&SweepOutcome) -> Option<f64>, format_value: impl Fn(f64) -> String) -> String {
+        const LABEL_WIDTH: usize = 340;
+        const CELL_WIDTH: usize = 110;
+        const CELL_HEIGHT: usize = 28;
+
+        let columns = self.outcomes.iter().map(|outcome| outcome.point.daily_reward).collect::<IndexSet<_>>();
+        let rows = self.outcomes.iter().map(|outcome| outcome.point.row_label()).collect::<IndexSet<_>>();
+        let values = self.outcomes.iter().filter_map(|outcome| value(outcome)).collect::<Vec<_>>();
+        let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+        let width = LABEL_WIDTH + CELL_WIDTH * columns.len();
+        let height = CELL_HEIGHT * (rows.len() + 2);
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\" font-size=\"12\">\n", width, height);
+        svg.push_str(&provenance::svg_comment());
+        svg.push_str(&format!("<text x=\"4\" y=\"18\" font-weight=\"bold\">{} ({}), daily reward in columns, not recovered in grey</text>\n", title, self.scenario.name()));
+        for (column, reward) in columns.iter().enumerate() {
+            svg.push_str(&format!("<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{} XRD</text>\n", LABEL_WIDTH + CELL_WIDTH * column + CELL_WIDTH / 2, CELL_HEIGHT * 2 - 8, reward));
+        }
+        for (row, label) in rows.iter().enumerate() {
+            let y = CELL_HEIGHT * (row + 2);
+            svg.push_str(&format!("<text x=\"4\" y=\"{}\">{}</text>\n", y + CELL_HEIGHT - 10, label));
+            for outcome in self.outcomes.iter().filter(|outcome| outcome.point.row_label() == *label) {
+                let column = columns.get_index_of(&outcome.point.daily_reward).unwrap();
+                let x = LABEL_WIDTH + CELL_WIDTH * column;
+                let (fill, text) = match value(outcome) {
+                    Some(value) => {
+                        let share = if max > min { (value - min) / (max - min) } else { 0.0 };
+                        // green for the lowest value, red for the highest
+                        (format!("hsl({:.0}, 70%, 60%)", 120.0 * (1.0 - share)), format_value(value))
+                    }
+                    None => ("#cccccc".to_string(), "-".to_string()),
+                };
+                svg.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"white\"/>\n", x, y, CELL_WIDTH, CELL_HEIGHT, fill));
+                svg.push_str(&format!("<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>\n", x + CELL_WIDTH / 2, y + CELL_HEIGHT - 10, text));
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
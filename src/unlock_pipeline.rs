@@ -0,0 +1,264 @@
+use std::path::Path;
+
+use radix_engine_common::prelude::*;
+use radix_engine_derive::ScryptoSbor;
+use radix_engine_interface::macros::dec;
+
+use crate::provenance;
+
+// Owner stake unlocks of the validator and unstake claim nfts in `unstaking_nft_vault` at one point in time.
+#[derive(Clone, Debug, Default)]
+pub struct PipelineState {
+    pub epoch: u64,
+    pub owner_unlocks: BTreeMap<u64, Decimal>,
+    // claim epoch and XRD amount by unstake claim nft
+    pub unstake_claims: IndexMap<NonFungibleLocalId, (u64, Decimal)>,
+    // balance of `liquidity_pool_vault`, where claimed XRD is delivered
+    pub liquidity_pool_xrd: Decimal,
+}
+
+#[derive(Clone, Debug, ScryptoSbor)]
+pub struct OwnerUnlock {
+    // None for unlocks already pending in the snapshot
+    pub started: Option<(u64, u64)>,
+    pub maturity_epoch: u64,
+    pub lsu: Decimal,
+    pub finished: Option<(u64, u64)>,
+    stuck_reported: bool,
+}
+
+#[derive(Clone, Debug, ScryptoSbor)]
+pub struct UnstakeClaim {
+    pub nft: NonFungibleLocalId,
+    // None for claims already in the vault in the snapshot
+    pub created: Option<(u64, u64)>,
+    pub claim_epoch: u64,
+    pub xrd: Decimal,
+    // indexes of `UnlockPipeline::unlocks` finished by the call which created the claim
+    pub unlocks: Vec<usize>,
+    pub claimed: Option<(u64, u64)>,
+    // share of the XRD delivered to `liquidity_pool_vault` by the claim call, pro rata to claim amounts
+    pub delivered_xrd: Option<Decimal>,
+    stuck_reported: bool,
+}
+
+impl UnstakeClaim {
+    pub fn is_redeemable(&self, epoch: u64) -> bool {
+        self.claimed.is_none() && self.claim_epoch <= epoch
+    }
+}
+
+#[derive(Clone, Debug, ScryptoSbor)]
+pub enum PipelineIssue {
+    // finish call without any matured unlock
+    DoubleFinish { day: u64, epoch: u64 },
+    // claim call without any claimable unstake
+    DoubleClaim { day: u64, epoch: u64 },
+    // matured unlock still pending after a finish call or a day after maturity
+    StuckUnlock { day: u64, epoch: u64, maturity_epoch: u64, lsu: Decimal },
+    // claimable unstake still in the vault a day after its claim epoch, after a claim call or at the end of the run
+    StuckClaim { day: u64, epoch: u64, nft: NonFungibleLocalId, claim_epoch: u64, xrd: Decimal },
+    // XRD delivered to `liquidity_pool_vault` by a claim call differs from amounts of claimed nfts
+    ClaimMismatch { day: u64, epoch: u64, expected: Decimal, delivered: Decimal },
+}
+
+#[derive(Clone, Debug, ScryptoSbor)]
+pub struct InFlight {
+    pub day: u64,
+    pub epoch: u64,
+    pub unlocking_lsu: Decimal,
+    // unlocking LSU valued at the redemption rate of the day
+    pub unlocking_xrd: Decimal,
+    pub unstaking_xrd: Decimal,
+}
+
+impl InFlight {
+    pub fn total_xrd(&self) -> Decimal {
+        self.unlocking_xrd + self.unstaking_xrd
+    }
+}
+
+#[derive(Clone, Debug, Default, ScryptoSbor)]
+pub struct UnlockPipeline {
+    pub unlocks: Vec<OwnerUnlock>,
+    pub claims: Vec<UnstakeClaim>,
+    pub issues: Vec<PipelineIssue>,
+    pub in_flight: Vec<InFlight>,
+}
+
+impl UnlockPipeline {
+    // unlocks and claims pending when the simulation starts
+    pub fn seed(&mut self, state: &PipelineState) {
+        for (maturity_epoch, lsu) in &state.owner_unlocks {
+            self.unlocks.push(OwnerUnlock { started: None, maturity_epoch: *maturity_epoch, lsu: *lsu, finished: None, stuck_reported: false });
+        }
+        for (nft, (claim_epoch, xrd)) in &state.unstake_claims {
+            self.claims.push(UnstakeClaim { nft: nft.clone(), created: None, claim_epoch: *claim_epoch, xrd: *xrd, unlocks: Vec::new(), claimed: None, delivered_xrd: None, stuck_reported: false });
+        }
+    }
+
+    pub fn record_start(&mut self, day: u64, before: &PipelineState, after: &PipelineState) {
+        for (maturity_epoch, lsu) in &after.owner_unlocks {
+            let started = *lsu - before.owner_unlocks.get(maturity_epoch).cloned().unwrap_or_default();
+            if started > dec!(0) {
+                self.unlocks.push(OwnerUnlock { started: Some((day, after.epoch)), maturity_epoch: *maturity_epoch, lsu: started, finished: None, stuck_reported: false });
+            }
+        }
+    }
+
+    pub fn record_finish(&mut self, day: u64, before: &PipelineState, after: &PipelineState) {
+        let epoch = after.epoch;
+        if !before.owner_unlocks.keys().any(|maturity_epoch| *maturity_epoch <= epoch) {
+            self.issues.push(PipelineIssue::DoubleFinish { day, epoch });
+        }
+        let mut finished = Vec::new();
+        for (index, unlock) in self.unlocks.iter_mut().enumerate().filter(|(_index, unlock)| unlock.finished.is_none() && unlock.maturity_epoch <= epoch) {
+            if after.owner_unlocks.contains_key(&unlock.maturity_epoch) {
+                unlock.stuck_reported = true;
+                self.issues.push(PipelineIssue::StuckUnlock { day, epoch, maturity_epoch: unlock.maturity_epoch, lsu: unlock.lsu });
+            } else {
+                unlock.finished = Some((day, epoch));
+                finished.push(index);
+            }
+        }
+        for (nft, (claim_epoch, xrd)) in &after.unstake_claims {
+            if !before.unstake_claims.contains_key(nft) {
+                self.claims.push(UnstakeClaim { nft: nft.clone(), created: Some((day, epoch)), claim_epoch: *claim_epoch, xrd: *xrd, unlocks: finished.clone(), claimed: None, delivered_xrd: None, stuck_reported: false });
+            }
+        }
+    }
+
+    pub fn record_claim(&mut self, day: u64, before: &PipelineState, after: &PipelineState) {
+        let epoch = after.epoch;
+        let claimed = self
+            .claims
+            .iter()
+            .enumerate()
+            .filter(|(_index, claim)| claim.claimed.is_none() && before.unstake_claims.contains_key(&claim.nft) && !after.unstake_claims.contains_key(&claim.nft))
+            .map(|(index, _claim)| index)
+            .collect::<Vec<_>>();
+        if claimed.is_empty() {
+            self.issues.push(PipelineIssue::DoubleClaim { day, epoch });
+        }
+        let expected = claimed.iter().fold(dec!(0), |sum, index| sum + self.claims[*index].xrd);
+        let delivered = after.liquidity_pool_xrd - before.liquidity_pool_xrd;
+        if expected != delivered {
+            self.issues.push(PipelineIssue::ClaimMismatch { day, epoch, expected, delivered });
+        }
+        for index in claimed {
+            let claim = &mut self.claims[index];
+            claim.claimed = Some((day, epoch));
+            claim.delivered_xrd = Some(if expected.is_zero() { dec!(0) } else { delivered * claim.xrd / expected });
+        }
+        self.report_unclaimed(day, epoch);
+    }
+
+    // flags claims which are redeemable at `epoch` but were not claimed
+    pub fn report_unclaimed(&mut self, day: u64, epoch: u64) {
+        for claim in self.claims.iter_mut().filter(|claim| claim.is_redeemable(epoch) && !claim.stuck_reported) {
+            claim.stuck_reported = true;
+            self.issues.push(PipelineIssue::StuckClaim { day, epoch, nft: claim.nft.clone(), claim_epoch: claim.claim_epoch, xrd: claim.xrd });
+        }
+    }
+
+    // records XRD in flight at the end of the day and reports unlocks and claims left behind for more than `stuck_after_epochs`
+    pub fn record_day(&mut self, day: u64, state: &PipelineState, redemption_rate: Decimal, stuck_after_epochs: u64) {
+        let epoch = state.epoch;
+        for unlock in self.unlocks.iter_mut().filter(|unlock| unlock.finished.is_none() && !unlock.stuck_reported) {
+            if unlock.maturity_epoch + stuck_after_epochs <= epoch {
+                unlock.stuck_reported = true;
+                self.issues.push(PipelineIssue::StuckUnlock { day, epoch, maturity_epoch: unlock.maturity_epoch, lsu: unlock.lsu });
+            }
+        }
+        for claim in self.claims.iter_mut().filter(|claim| claim.claimed.is_none() && !claim.stuck_reported) {
+            if claim.claim_epoch + stuck_after_epochs <= epoch {
+                claim.stuck_reported = true;
+                self.issues.push(PipelineIssue::StuckClaim { day, epoch, nft: claim.nft.clone(), claim_epoch: claim.claim_epoch, xrd: claim.xrd });
+            }
+        }
+        let unlocking_lsu = state.owner_unlocks.values().fold(dec!(0), |sum, lsu| sum + *lsu);
+        self.in_flight.push(InFlight {
+            day,
+            epoch,
+            unlocking_lsu,
+            unlocking_xrd: unlocking_lsu * redemption_rate,
+            unstaking_xrd: state.unstake_claims.values().fold(dec!(0), |sum, (_claim_epoch, xrd)| sum + *xrd),
+        });
+    }
+
+    pub fn print_summary(&self) {
+        let finished = self.unlocks.iter().filter(|unlock| unlock.finished.is_some()).count();
+        let claimed = self.claims.iter().filter(|claim| claim.claimed.is_some()).count();
+        println!("Owner unlocks: {}, finished: {}, unstake claims: {}, claimed: {}", self.unlocks.len(), finished, self.claims.len(), claimed);
+        if let Some(max) = self.in_flight.iter().max_by_key(|in_flight| in_flight.total_xrd()) {
+            println!("-- Most XRD in flight: {} on day {}", max.total_xrd(), max.day);
+        }
+        for issue in &self.issues {
+            match issue {
+                PipelineIssue::DoubleFinish { day, epoch } => println!("-- Day {}, epoch {}: finish without matured unlock", day, epoch),
+                PipelineIssue::DoubleClaim { day, epoch } => println!("-- Day {}, epoch {}: claim without claimable unstake", day, epoch),
+                PipelineIssue::StuckUnlock { day, epoch, maturity_epoch, lsu } => println!("-- Day {}, epoch {}: unlock of {} LSU matured at epoch {} is not finished", day, epoch, lsu, maturity_epoch),
+                PipelineIssue::StuckClaim { day, epoch, nft, claim_epoch, xrd } => println!("-- Day {}, epoch {}: unstake claim {} of {} XRD claimable at epoch {} is not claimed", day, epoch, nft, xrd, claim_epoch),
+                PipelineIssue::ClaimMismatch { day, epoch, expected, delivered } => println!("-- Day {}, epoch {}: claim delivered {} XRD to the liquidity pool vault instead of {}", day, epoch, delivered, expected),
+            }
+        }
+    }
+
+    // every unstake claim nft from the unlocks which created it to the XRD it delivered
+    pub fn print_claim_lifecycle(&self) {
+        let day_epoch = |point: Option<(u64, u64)>| point.map(|(day, epoch)| format!("day {} (epoch {})", day, epoch)).unwrap_or("-".to_string());
+        for claim in &self.claims {
+            let unlocks = claim
+                .unlocks
+                .iter()
+                .map(|index| format!("{} LSU matured at epoch {}", self.unlocks[*index].lsu, self.unlocks[*index].maturity_epoch))
+                .collect::<Vec<_>>();
+            println!(
+                "-- Claim {}: created {} from [{}], {} XRD claimable at epoch {}, claimed {}, delivered {} XRD",
+                claim.nft,
+                day_epoch(claim.created),
+                unlocks.join(", "),
+                claim.xrd,
+                claim.claim_epoch,
+                day_epoch(claim.claimed),
+                claim.delivered_xrd.map(|xrd| xrd.to_string()).unwrap_or("-".to_string()),
+            );
+        }
+    }
+
+    pub fn write_claims_csv(&self, path: &Path) {
+        let mut csv = provenance::csv_writer(path);
+        csv.write_record(["Claim NFT", "Created day", "Created epoch", "Unlocked LSU", "Claim epoch", "XRD", "Claimed day", "Claimed epoch", "Delivered XRD"]).unwrap();
+        for claim in &self.claims {
+            let unlocked_lsu = claim.unlocks.iter().fold(dec!(0), |sum, index| sum + self.unlocks[*index].lsu);
+            csv.write_record(&[
+                claim.nft.to_string(),
+                claim.created.map(|(day, _epoch)| day.to_string()).unwrap_or_default(),
+                claim.created.map(|(_day, epoch)| epoch.to_string()).unwrap_or_default(),
+                unlocked_lsu.to_string(),
+                claim.claim_epoch.to_string(),
+                claim.xrd.to_string(),
+                claim.claimed.map(|(day, _epoch)| day.to_string()).unwrap_or_default(),
+                claim.claimed.map(|(_day, epoch)| epoch.to_string()).unwrap_or_default(),
+                claim.delivered_xrd.map(|xrd| xrd.to_string()).unwrap_or_default(),
+            ]).unwrap();
+        }
+        csv.flush().unwrap();
+    }
+
+    pub fn write_csv(&self, path: &Path) {
+        let mut csv = provenance::csv_writer(path);
+        csv.write_record(["Day", "Epoch", "Unlocking LSU", "Unlocking (XRD)", "Unstaking (XRD)", "In flight (XRD)"]).unwrap();
+        for in_flight in &self.in_flight {
+            csv.write_record(&[
+                in_flight.day.to_string(),
+                in_flight.epoch.to_string(),
+                in_flight.unlocking_lsu.to_string(),
+                in_flight.unlocking_xrd.to_string(),
+                in_flight.unstaking_xrd.to_string(),
+                in_flight.total_xrd().to_string(),
+            ]).unwrap();
+        }
+        csv.flush().unwrap();
+    }
+}
@@ -0,0 +1,175 @@
+use std::path::Path;
+
+use radix_engine_common::prelude::*;
+use radix_engine_interface::macros::dec;
+
+use crate::fees::FeeLedger;
+use crate::gable_simulation::GableSimulation;
+use crate::provenance;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WithdrawalMode {
+    // `withdraw_lsu` of whole pool NFTs, as far as pool liquidity covers their claims
+    WholeNft,
+    // hypothetical entry point (patched blueprint or companion contract) paying every claim a pro-rata slice of pool liquidity
+    ProRata,
+}
+
+impl WithdrawalMode {
+    pub const ALL: [WithdrawalMode; 2] = [WithdrawalMode::WholeNft, WithdrawalMode::ProRata];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WithdrawalMode::WholeNft => "whole",
+            WithdrawalMode::ProRata => "pro-rata",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<WithdrawalMode> {
+        WithdrawalMode::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+}
+
+// Share of each claim not paid yet by pro-rata withdrawals.
+// The component still accrues rewards to the whole NFT, so later slices are slightly overstated.
+#[derive(Clone, Debug, Default)]
+pub struct PartialWithdrawals {
+    remaining: IndexMap<NonFungibleLocalId, Decimal>,
+}
+
+impl PartialWithdrawals {
+    // LSU and XRD claims reduced to their unpaid share, fully paid NFTs are removed
+    pub fn outstanding(&self, claims: IndexMap<NonFungibleLocalId, (Decimal, Decimal)>) -> IndexMap<NonFungibleLocalId, (Decimal, Decimal)> {
+        claims
+            .into_iter()
+            .filter_map(|(nft, (lsu, xrd))| {
+                let remaining = self.remaining.get(&nft).cloned().unwrap_or(dec!(1));
+                (!remaining.is_zero()).then(|| (nft, (lsu * remaining, xrd * remaining)))
+            })
+            .collect()
+    }
+
+    // pays every outstanding claim the same share, all of them if pool liquidity covers the total, returns withdrawn LSU
+    pub fn withdraw(&mut self, simulation: &mut GableSimulation, claims: &IndexMap<NonFungibleLocalId, (Decimal, Decimal)>, pool_liquidity: Decimal, ledger: &mut WithdrawalLedger) -> Decimal {
+        let total_xrd = claims.values().fold(dec!(0), |sum, (_lsu, xrd)| sum + *xrd);
+        if pool_liquidity <= dec!(0) || total_xrd.is_zero() {
+            return dec!(0);
+        }
+        let share = if total_xrd <= pool_liquidity { dec!(1) } else { pool_liquidity / total_xrd };
+        let mut withdrawn_lsu = dec!(0);
+        let mut withdrawn_xrd = dec!(0);
+        for (nft, (lsu, xrd)) in claims {
+            let remaining = self.remaining.entry(nft.clone()).or_insert(dec!(1));
+            *remaining = if share == dec!(1) { dec!(0) } else { *remaining * (dec!(1) - share) };
+            ledger.record(simulation.day, nft, *lsu * share, *xrd * share, remaining.is_zero());
+            withdrawn_lsu += *lsu * share;
+            withdrawn_xrd += *xrd * share;
+        }
+        println!("-- Pro-rata withdrawal of {}% of {} claims: {} XRD and {} LSU", share * dec!(100), claims.len(), withdrawn_xrd, withdrawn_lsu);
+        simulation.transfer_partial_withdrawal(simulation.account, withdrawn_xrd, withdrawn_lsu);
+        withdrawn_lsu
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Payout {
+    pub day: u64,
+    pub nft: NonFungibleLocalId,
+    pub lsu: Decimal,
+    pub xrd: Decimal,
+    // the claim is fully paid with this payout
+    pub complete: bool,
+}
+
+// Payouts of both withdrawal modes, whole NFT withdrawals are a single complete payout.
+#[derive(Clone, Debug, Default)]
+pub struct WithdrawalLedger {
+    pub payouts: Vec<Payout>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct WithdrawalFairness {
+    pub nfts_paid: usize,
+    pub nfts_completed: usize,
+    pub xrd_paid: Decimal,
+    // mean day on which XRD was paid, weighted by amount
+    pub xrd_weighted_day: f64,
+    pub mean_first_payout_day: f64,
+    pub mean_completion_day: f64,
+    pub max_completion_day: u64,
+    // fees of `withdraw_lsu` transactions, None for pro-rata payouts which are vault writes without transactions
+    pub transaction_fees: Option<Decimal>,
+}
+
+impl WithdrawalLedger {
+    pub fn record(&mut self, day: u64, nft: &NonFungibleLocalId, lsu: Decimal, xrd: Decimal, complete: bool) {
+        self.payouts.push(Payout { day, nft: nft.clone(), lsu, xrd, complete });
+    }
+
+    pub fn fairness(&self, mode: WithdrawalMode, fees: &FeeLedger) -> WithdrawalFairness {
+        let to_f64 = |amount: Decimal| amount.to_string().parse::<f64>().unwrap_or_default();
+        let mut first_payouts: IndexMap<&NonFungibleLocalId, u64> = IndexMap::new();
+        let mut completions = Vec::new();
+        let mut xrd_paid = dec!(0);
+        let mut xrd_days = 0.0;
+        for payout in &self.payouts {
+            first_payouts.entry(&payout.nft).or_insert(payout.day);
+            if payout.complete {
+                completions.push(payout.day);
+            }
+            xrd_paid += payout.xrd;
+            xrd_days += to_f64(payout.xrd) * payout.day as f64;
+        }
+        let mean = |days: Vec<u64>| if days.is_empty() { 0.0 } else { days.iter().sum::<u64>() as f64 / days.len() as f64 };
+        WithdrawalFairness {
+            nfts_paid: first_payouts.len(),
+            nfts_completed: completions.len(),
+            xrd_paid,
+            xrd_weighted_day: if xrd_paid.is_zero() { 0.0 } else { xrd_days / to_f64(xrd_paid) },
+            mean_first_payout_day: mean(first_payouts.values().cloned().collect()),
+            max_completion_day: completions.iter().cloned().max().unwrap_or(0),
+            mean_completion_day: mean(completions),
+            transaction_fees: match mode {
+                WithdrawalMode::WholeNft => Some(fees.per_operation().get("withdraw_lsu").map(|totals| totals.total_cost).unwrap_or_default()),
+                WithdrawalMode::ProRata => None,
+            },
+        }
+    }
+
+    pub fn write_csv(&self, path: &Path) {
+        let mut csv = provenance::csv_writer(path);
+        csv.write_record(["Day", "NFT", "LSU", "XRD", "Complete"]).unwrap();
+        for payout in &self.payouts {
+            csv.write_record(&[payout.day.to_string(), payout.nft.to_string(), payout.lsu.to_string(), payout.xrd.to_string(), payout.complete.to_string()]).unwrap();
+        }
+        csv.flush().unwrap();
+    }
+}
+
+impl WithdrawalFairness {
+    pub fn print_header() {
+        println!(
+            "{:<10} {:>10} {:>10} {:>30} {:>16} {:>16} {:>16} {:>16} {:>28}",
+            "Mode", "NFTs paid", "Completed", "XRD paid", "XRD mean day", "First payout", "Completion", "Last completion", "Withdraw fees (XRD)"
+        );
+    }
+
+    pub fn print_notes() {
+        println!("-- Pro-rata payouts are written to the pool vaults directly, so they pay no transaction fees");
+    }
+
+    pub fn print(&self, mode: WithdrawalMode) {
+        println!(
+            "{:<10} {:>10} {:>10} {:>30} {:>16.1} {:>16.1} {:>16.1} {:>16} {:>28}",
+            mode.name(),
+            self.nfts_paid,
+            self.nfts_completed,
+            self.xrd_paid,
+            self.xrd_weighted_day,
+            self.mean_first_payout_day,
+            self.mean_completion_day,
+            self.max_completion_day,
+            self.transaction_fees.map(|fees| fees.to_string()).unwrap_or("not charged".to_string())
+        );
+    }
+}